@@ -0,0 +1,205 @@
+// 游戏板模块：存储已锁定的方块
+
+use crate::constants::*;
+use crate::tetromino::TetrominoType;
+
+/// 占满一整行时的行位图
+pub const FULL_ROW: u16 = (1 << GRID_WIDTH) - 1;
+
+/// 游戏板
+/// 存储游戏板上已锁定方块的信息。占用情况以位图表示（每行一个 u16，第 x 位对应第 x 列），
+/// 碰撞、满行判定和消行都通过位运算完成；方块类型另外存储，只用于显示
+#[derive(Clone, PartialEq, Debug)]
+pub struct Board {
+    /// 每行的占用位图，前 BUFFER_HEIGHT 行为可见区域上方的隐藏缓冲区
+    rows: [u16; TOTAL_HEIGHT],
+    /// 每个单元格的方块类型（None 表示空），与占用位图保持一致
+    cells: [[Option<TetrominoType>; GRID_WIDTH]; TOTAL_HEIGHT],
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self {
+            rows: [0; TOTAL_HEIGHT],
+            cells: [[None; GRID_WIDTH]; TOTAL_HEIGHT],
+        }
+    }
+}
+
+impl Board {
+    /// 检查指定单元格是否为空
+    pub fn is_empty(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & (1 << x) == 0
+    }
+
+    /// 获取指定单元格的方块类型（None表示空）
+    pub fn cell(&self, x: usize, y: usize) -> Option<TetrominoType> {
+        self.cells[y][x]
+    }
+
+    /// 获取指定行的占用位图（第 x 位对应第 x 列）
+    pub fn row(&self, y: usize) -> u16 {
+        self.rows[y]
+    }
+
+    /// 设置指定单元格的方块类型
+    ///
+    /// # 参数
+    /// * `x` - 列坐标
+    /// * `y` - 行坐标
+    /// * `cell` - 要设置的方块类型（None表示清空）
+    pub fn set_cell(&mut self, x: usize, y: usize, cell: Option<TetrominoType>) {
+        if cell.is_some() {
+            self.rows[y] |= 1 << x;
+        } else {
+            self.rows[y] &= !(1 << x);
+        }
+        self.cells[y][x] = cell;
+    }
+
+    /// 检查方块放在指定位置时是否与墙壁、地面、顶部或已锁定的方块重叠
+    ///
+    /// # 参数
+    /// * `masks` - 方块包围盒内每行的占用位图（见 `TetrominoType::row_masks`）
+    /// * `x` - 包围盒左上角的列坐标
+    /// * `y` - 包围盒左上角的行坐标
+    pub fn collides(&self, masks: &[u16; 4], x: i32, y: i32) -> bool {
+        for (dy, &mask) in masks.iter().enumerate() {
+            if mask == 0 {
+                continue;
+            }
+            let row = y + dy as i32;
+            if row < 0 || row >= TOTAL_HEIGHT as i32 {
+                return true;
+            }
+            // 平移到目标列，移出左右边界的格子视为与墙壁碰撞
+            let shifted = if x < 0 {
+                if mask & ((1 << -x) - 1) != 0 {
+                    return true;
+                }
+                mask >> -x
+            } else {
+                let shifted = (mask as u32) << x;
+                if shifted & !(FULL_ROW as u32) != 0 {
+                    return true;
+                }
+                shifted as u16
+            };
+            if self.rows[row as usize] & shifted != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 检查除指定行外游戏板是否已全部清空（全消判定）
+    ///
+    /// # 参数
+    /// * `cleared` - 即将被消除的满行（不计入判定）
+    pub fn is_all_clear(&self, cleared: &[usize]) -> bool {
+        self.rows
+            .iter()
+            .enumerate()
+            .all(|(y, &row)| row == 0 || cleared.contains(&y))
+    }
+
+    /// 获取所有已满的行号（从上到下排列）
+    pub fn full_lines(&self) -> Vec<usize> {
+        (0..TOTAL_HEIGHT)
+            .filter(|&y| self.is_line_full(y))
+            .collect()
+    }
+
+    /// 消除指定的行，并将上方的行依次下移
+    ///
+    /// # 参数
+    /// * `lines` - 要消除的行号
+    pub fn remove_lines(&mut self, lines: &[usize]) {
+        // 从下往上把保留的行依次压实到底部，顶部空出的行清空
+        let mut target = TOTAL_HEIGHT;
+        for y in (0..TOTAL_HEIGHT).rev() {
+            if lines.contains(&y) {
+                continue;
+            }
+            target -= 1;
+            if target != y {
+                self.rows[target] = self.rows[y];
+                self.cells[target] = self.cells[y];
+            }
+        }
+        self.rows[..target].fill(0);
+        self.cells[..target].fill([None; GRID_WIDTH]);
+    }
+
+    /// 检查指定行是否已满（所有单元格都有方块）
+    pub fn is_line_full(&self, line: usize) -> bool {
+        self.rows[line] == FULL_ROW
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 填满指定行，`gap` 为留空的列
+    fn fill_row(board: &mut Board, y: usize, gap: Option<usize>) {
+        for x in (0..GRID_WIDTH).filter(|&x| Some(x) != gap) {
+            board.set_cell(x, y, Some(TetrominoType::I));
+        }
+    }
+
+    #[test]
+    fn full_lines_only_reports_complete_rows() {
+        let mut board = Board::default();
+        fill_row(&mut board, TOTAL_HEIGHT - 1, None);
+        fill_row(&mut board, TOTAL_HEIGHT - 2, Some(4));
+        fill_row(&mut board, TOTAL_HEIGHT - 3, None);
+
+        assert_eq!(board.row(TOTAL_HEIGHT - 1), FULL_ROW);
+        assert_eq!(board.row(TOTAL_HEIGHT - 2), FULL_ROW & !(1 << 4));
+        assert_eq!(board.full_lines(), vec![TOTAL_HEIGHT - 3, TOTAL_HEIGHT - 1]);
+    }
+
+    #[test]
+    fn remove_lines_shifts_rows_above_down() {
+        let mut board = Board::default();
+        fill_row(&mut board, TOTAL_HEIGHT - 1, None);
+        fill_row(&mut board, TOTAL_HEIGHT - 2, Some(4));
+        fill_row(&mut board, TOTAL_HEIGHT - 3, None);
+        board.set_cell(7, TOTAL_HEIGHT - 4, Some(TetrominoType::T));
+
+        board.remove_lines(&board.full_lines());
+
+        // 未满的行和其上方的格子各下移两行，位图和方块类型保持一致
+        assert_eq!(board.row(TOTAL_HEIGHT - 1), FULL_ROW & !(1 << 4));
+        assert_eq!(board.row(TOTAL_HEIGHT - 2), 1 << 7);
+        assert_eq!(board.cell(7, TOTAL_HEIGHT - 2), Some(TetrominoType::T));
+        assert!(board.is_empty(4, TOTAL_HEIGHT - 1));
+        assert!((0..TOTAL_HEIGHT - 2).all(|y| board.row(y) == 0));
+        assert!(board.full_lines().is_empty());
+    }
+
+    #[test]
+    fn all_clear_ignores_rows_being_cleared() {
+        let mut board = Board::default();
+        fill_row(&mut board, TOTAL_HEIGHT - 1, None);
+        assert!(board.is_all_clear(&[TOTAL_HEIGHT - 1]));
+
+        board.set_cell(0, TOTAL_HEIGHT - 2, Some(TetrominoType::O));
+        assert!(!board.is_all_clear(&[TOTAL_HEIGHT - 1]));
+    }
+
+    #[test]
+    fn collides_with_walls_floor_and_blocks() {
+        let mut board = Board::default();
+        let masks = TetrominoType::O.row_masks(0);
+        assert!(!board.collides(masks, 0, 0));
+        assert!(board.collides(masks, -1, 0));
+        assert!(board.collides(masks, GRID_WIDTH as i32 - 1, 0));
+        assert!(board.collides(masks, 0, TOTAL_HEIGHT as i32 - 1));
+
+        board.set_cell(1, 1, Some(TetrominoType::T));
+        assert!(board.collides(masks, 0, 0));
+        assert!(!board.collides(masks, 2, 0));
+    }
+}
@@ -0,0 +1,123 @@
+// 游戏常量定义
+
+/// 游戏板宽度（列数）
+pub const GRID_WIDTH: usize = 10;
+/// 游戏板可见区域高度（行数）
+pub const GRID_HEIGHT: usize = 20;
+/// 可见区域上方隐藏缓冲区的高度（行数）
+pub const BUFFER_HEIGHT: usize = 20;
+/// 游戏板总高度（隐藏缓冲区 + 可见区域）
+pub const TOTAL_HEIGHT: usize = BUFFER_HEIGHT + GRID_HEIGHT;
+/// 每升一级需要消除的行数
+pub const LINES_PER_LEVEL: u32 = 10;
+/// 马拉松模式通关需要消除的行数
+pub const MARATHON_LINES: u32 = 150;
+/// 可选择的最高起始等级
+pub const MAX_START_LEVEL: u32 = 15;
+/// 记录分段用时的间隔行数
+pub const SPLIT_LINES: u32 = 10;
+/// 重力曲线的最高等级（超过该等级后下落速度不再增加）
+pub const MAX_GRAVITY_LEVEL: u32 = 20;
+/// 模拟频率（每秒模拟的帧数），所有计时都以帧为单位
+pub const FRAME_RATE: u32 = 60;
+/// 落地延迟时间（帧）
+pub const LOCK_DELAY_FRAMES: u32 = 12;
+/// 移动重置模式下，每个方块最多可重置锁定计时器的次数
+pub const MAX_LOCK_RESETS: u32 = 15;
+/// 预览队列的最大长度（最多同时显示的后续方块数）
+pub const MAX_PREVIEW_COUNT: usize = 6;
+
+// 消行计分表
+/// 消一行的分数
+pub const SCORE_1_LINE: u32 = 100;
+/// 消两行的分数
+pub const SCORE_2_LINES: u32 = 300;
+/// 消三行的分数
+pub const SCORE_3_LINES: u32 = 500;
+/// 消四行（俄罗斯方块）的分数
+pub const SCORE_4_LINES: u32 = 800;
+
+// T-spin 计分表
+/// 迷你 T-spin（不消行）的分数
+pub const SCORE_T_SPIN_MINI: u32 = 100;
+/// 迷你 T-spin 消一行的分数
+pub const SCORE_T_SPIN_MINI_SINGLE: u32 = 200;
+/// 迷你 T-spin 消两行的分数
+pub const SCORE_T_SPIN_MINI_DOUBLE: u32 = 400;
+/// T-spin（不消行）的分数
+pub const SCORE_T_SPIN: u32 = 400;
+/// T-spin 消一行的分数
+pub const SCORE_T_SPIN_SINGLE: u32 = 800;
+/// T-spin 消两行的分数
+pub const SCORE_T_SPIN_DOUBLE: u32 = 1200;
+/// T-spin 消三行的分数
+pub const SCORE_T_SPIN_TRIPLE: u32 = 1600;
+
+// 奖励计分表
+/// 连击奖励：每级连击的分数
+pub const SCORE_COMBO: u32 = 50;
+/// 全消（Perfect Clear）消一行的奖励分数
+pub const SCORE_PERFECT_CLEAR_1_LINE: u32 = 800;
+/// 全消消两行的奖励分数
+pub const SCORE_PERFECT_CLEAR_2_LINES: u32 = 1200;
+/// 全消消三行的奖励分数
+pub const SCORE_PERFECT_CLEAR_3_LINES: u32 = 1800;
+/// 全消消四行的奖励分数
+pub const SCORE_PERFECT_CLEAR_4_LINES: u32 = 2000;
+/// 背靠背消四行全消的奖励分数
+pub const SCORE_PERFECT_CLEAR_B2B_4_LINES: u32 = 3200;
+/// 软降每下落一格的分数
+pub const SCORE_SOFT_DROP_CELL: u32 = 1;
+/// 硬降每下落一格的分数
+pub const SCORE_HARD_DROP_CELL: u32 = 2;
+/// 奖励提示文字的显示时间（帧）
+pub const CALLOUT_FRAMES: u32 = 90;
+
+/// 重力的细分单位：方块下落一行等于 GRAVITY_UNIT 个单位
+pub const GRAVITY_UNIT: u32 = 1 << 16;
+
+/// 各等级每帧的重力（以 GRAVITY_UNIT 为单位，下标 0 为 1 级）
+///
+/// 由标准规则的重力公式 (0.8 - (等级 - 1) × 0.007)^(等级 - 1) 秒/行换算为每帧下落的距离，
+/// 预先算成整数表，保证在所有平台上得到相同的结果（15 级约 2.4G，19 级起为 20G）
+const GRAVITY_TABLE: [u32; MAX_GRAVITY_LEVEL as usize] = [
+    1092, 1377, 1768, 2311, 3075, 4169, 5759, 8107, 11634, 17026, 25416, 38709, 60169, 95483,
+    154742, 256187, 433425, 749597, 1310720, 1310720,
+];
+
+/// 根据等级获取每帧的重力（以 GRAVITY_UNIT 为单位，累计满 GRAVITY_UNIT 下落一行）
+pub fn gravity(level: u32) -> u32 {
+    GRAVITY_TABLE[(level.clamp(1, MAX_GRAVITY_LEVEL) - 1) as usize]
+}
+
+/// 将帧数换算为毫秒（向下取整）
+pub fn frames_to_ms(frames: u32) -> u64 {
+    frames as u64 * 1000 / FRAME_RATE as u64
+}
+
+/// 将毫秒换算为帧数（四舍五入）
+///
+/// 使用 u64 计算，避免很大的毫秒数相乘时溢出
+pub fn ms_to_frames(ms: u32) -> u32 {
+    let frames = (ms as u64 * FRAME_RATE as u64 + 500) / 1000;
+    u32::try_from(frames).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ms_to_frames_rounds_to_the_nearest_frame() {
+        assert_eq!(ms_to_frames(0), 0);
+        assert_eq!(ms_to_frames(8), 0);
+        assert_eq!(ms_to_frames(9), 1);
+        assert_eq!(ms_to_frames(167), 10);
+        assert_eq!(ms_to_frames(1000), FRAME_RATE);
+    }
+
+    #[test]
+    fn ms_to_frames_does_not_overflow() {
+        assert_eq!(ms_to_frames(u32::MAX), 257_698_038);
+    }
+}
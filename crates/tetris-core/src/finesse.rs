@@ -0,0 +1,127 @@
+// 按键效率模块：计算把方块移到目标位置所需的最少操作次数
+
+use crate::board::Board;
+use crate::tetromino::{ActivePiece, Rotation};
+use std::collections::{HashSet, VecDeque};
+
+/// 计算在空游戏板上把方块从起始位置移到目标落点所需的最少操作次数
+///
+/// 每次操作为一次按键：左右移动一格、按住左右键移到墙边（DAS）或一次旋转。
+/// 只比较方块占据的列和形状（不比较高度），因此 I、S、Z、O 等对称方块的
+/// 等价朝向都视为同一落点
+///
+/// # 返回
+/// 最少操作次数，目标落点无法到达时返回 None
+pub fn min_inputs(start: &ActivePiece, target: &ActivePiece) -> Option<u32> {
+    let board = Board::default();
+    let goal = footprint(target);
+    let mut visited = HashSet::from([(start.x, start.rotation)]);
+    let mut queue = VecDeque::from([(*start, 0)]);
+    while let Some((piece, count)) = queue.pop_front() {
+        if footprint(&piece) == goal {
+            return Some(count);
+        }
+        for next in moves(&piece, &board) {
+            if visited.insert((next.x, next.rotation)) {
+                queue.push_back((next, count + 1));
+            }
+        }
+    }
+    None
+}
+
+/// 获取一次操作后可以到达的所有位置
+fn moves(piece: &ActivePiece, board: &Board) -> Vec<ActivePiece> {
+    let mut moves = Vec::new();
+    for dx in [-1, 1] {
+        if piece.check_collision(dx, 0, board) {
+            continue;
+        }
+        // 移动一格
+        moves.push(ActivePiece {
+            x: piece.x + dx,
+            ..*piece
+        });
+        // 按住移到墙边
+        let mut shifted = *piece;
+        while !shifted.check_collision(dx, 0, board) {
+            shifted.x += dx;
+        }
+        moves.push(shifted);
+    }
+    for direction in [
+        Rotation::Clockwise,
+        Rotation::CounterClockwise,
+        Rotation::Half,
+    ] {
+        moves.extend(piece.try_rotate(direction, board));
+    }
+    moves
+}
+
+/// 获取方块占据的格子（去掉高度，按列和相对高度排序）
+fn footprint(piece: &ActivePiece) -> Vec<(i32, i32)> {
+    let blocks = piece.blocks();
+    let top = blocks.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let mut cells: Vec<_> = blocks.into_iter().map(|(x, y)| (x, y - top)).collect();
+    cells.sort_unstable();
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tetromino::TetrominoType;
+
+    /// 在生成高度、指定列和朝向的方块
+    fn at(tetromino_type: TetrominoType, x: i32, rotation: u8) -> ActivePiece {
+        ActivePiece {
+            x,
+            rotation,
+            ..ActivePiece::new(tetromino_type)
+        }
+    }
+
+    /// 从出生位置到目标位置的最少操作次数
+    fn inputs(tetromino_type: TetrominoType, x: i32, rotation: u8) -> Option<u32> {
+        min_inputs(
+            &ActivePiece::new(tetromino_type),
+            &at(tetromino_type, x, rotation),
+        )
+    }
+
+    #[test]
+    fn single_taps_and_rotations() {
+        assert_eq!(inputs(TetrominoType::T, 3, 0), Some(0));
+        assert_eq!(inputs(TetrominoType::T, 2, 0), Some(1));
+        assert_eq!(inputs(TetrominoType::T, 5, 0), Some(2));
+        assert_eq!(inputs(TetrominoType::T, 3, 1), Some(1));
+        assert_eq!(inputs(TetrominoType::T, 3, 2), Some(1));
+        assert_eq!(inputs(TetrominoType::T, 2, 3), Some(2));
+    }
+
+    #[test]
+    fn shifting_to_the_wall_is_one_input() {
+        assert_eq!(inputs(TetrominoType::T, 0, 0), Some(1));
+        assert_eq!(inputs(TetrominoType::T, 7, 0), Some(1));
+        assert_eq!(inputs(TetrominoType::J, 1, 0), Some(2));
+        // 竖直的 I 贴墙：旋转后再移到墙边
+        assert_eq!(inputs(TetrominoType::I, -2, 1), Some(2));
+        assert_eq!(inputs(TetrominoType::I, 7, 1), Some(2));
+    }
+
+    #[test]
+    fn symmetric_orientations_count_as_the_same_placement() {
+        // S、Z 的 0 和 2 朝向占据相同的格子，目标为 2 朝向时也无需旋转
+        assert_eq!(inputs(TetrominoType::S, 3, 2), Some(0));
+        assert_eq!(inputs(TetrominoType::Z, 3, 2), Some(0));
+        // 竖直的 S、Z、I 用任一方向旋转到达同一列都只需一次
+        assert_eq!(inputs(TetrominoType::S, 4, 3), Some(1));
+        assert_eq!(inputs(TetrominoType::Z, 4, 3), Some(1));
+        assert_eq!(inputs(TetrominoType::I, 4, 3), Some(1));
+        assert_eq!(inputs(TetrominoType::I, 3, 1), Some(1));
+        // O 的所有朝向都相同
+        assert_eq!(inputs(TetrominoType::O, 4, 2), Some(0));
+        assert_eq!(inputs(TetrominoType::O, 0, 1), Some(1));
+    }
+}
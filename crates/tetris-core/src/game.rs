@@ -0,0 +1,1371 @@
+// 游戏模拟模块：游戏状态和规则逻辑
+// 不依赖任何引擎，以固定帧率模拟，每次调用 step(inputs) 推进一帧
+
+use crate::board::Board;
+use crate::constants::*;
+use crate::finesse;
+use crate::input::{AutoShift, Inputs};
+use crate::mode::GameMode;
+use crate::random::Rng;
+use crate::randomizer::Randomizer;
+use crate::settings::{LockDelayMode, Settings};
+use crate::tetromino::{ActivePiece, Rotation, TSpin, TetrominoType};
+use std::collections::VecDeque;
+
+/// 游戏
+/// 管理一局游戏的游戏板、方块和得分，并按规则推进模拟
+#[derive(Clone, Debug)]
+pub struct Game {
+    /// 游戏板（已锁定的方块）
+    pub board: Board,
+    /// 游戏设置（操作手感和规则选项）
+    pub settings: Settings,
+    /// 当前下落的方块（None表示没有活跃方块）
+    pub current_piece: Option<ActivePiece>,
+    /// 后续方块队列（队首为下一个将要出现的方块，始终保持 MAX_PREVIEW_COUNT 个）
+    pub next_queue: VecDeque<TetrominoType>,
+    /// 暂存区中的方块类型（None表示暂存区为空）
+    pub hold_piece: Option<TetrominoType>,
+    /// 当前方块是否已经使用过暂存（每个方块锁定前只能暂存一次）
+    pub hold_used: bool,
+    /// 当前分数
+    pub score: u32,
+    /// 累计消除的行数
+    pub lines: u32,
+    /// 当前等级（从起始等级开始，每消除 LINES_PER_LEVEL 行升一级）
+    pub level: u32,
+    /// 连击数（None 表示没有连续消行，首次消行为 0，之后每次连续消行加 1）
+    pub combo: Option<u32>,
+    /// 上一次消行是否为高难度消行（消四行或 T-spin 消行），用于背靠背奖励
+    pub back_to_back: bool,
+    /// 当前显示的奖励提示（如 "B2B TETRIS"、"3 COMBO"）
+    pub callout: Option<Callout>,
+    /// 当前游戏阶段（下落、消行等待、出块等待）
+    pub phase: GamePhase,
+    /// 出块等待期间按住的操作，在下一个方块生成时应用（IRS/IHS）
+    pub initial_actions: InitialActions,
+    /// 游戏是否结束
+    pub game_over: bool,
+    /// 游戏结束的原因（None 表示游戏尚未结束或达成了模式目标）
+    pub top_out: Option<TopOutReason>,
+    /// 是否达成了模式目标（如冲线模式消除了目标行数）
+    pub completed: bool,
+    /// 已进行的帧数（游戏结束后停止计时）
+    pub frames: u32,
+    /// 已锁定的方块数
+    pub pieces: u32,
+    /// 冲线模式每消除 SPLIT_LINES 行时的帧数（分段用时，其他模式不记录）
+    pub splits: Vec<u32>,
+    /// 按键效率失误次数（放置方块使用的操作多于最少操作次数的总和，只在冲线模式统计）
+    pub finesse_faults: u32,
+    /// 按得分来源统计的分数明细（各消行类型的次数和得分、奖励分和下落分）
+    pub score_breakdown: ScoreBreakdown,
+    /// 本局的随机种子（相同种子总是产生相同的方块序列）
+    seed: u64,
+    /// 由种子初始化的随机数生成器
+    rng: Rng,
+    /// 决定方块出现顺序的随机器（由设置中的随机器类型创建）
+    randomizer: Box<dyn Randomizer>,
+    /// 锁定延迟计时器（None表示未开始计时，Some(帧数)表示已触底并计时）
+    pub lock_timer: Option<u32>,
+    /// 刚锁定方块的 T-spin 判定结果（None 表示还没有待结算的锁定）
+    pub locked_spin: Option<TSpin>,
+    /// 当前方块已使用的锁定计时器重置次数（移动重置模式）
+    pub lock_resets: u32,
+    /// 当前方块到达过的最低行（到达新的最低行时重置计时器和重置次数）
+    pub lowest_row: i32,
+    /// 重力累计的下落距离（以 GRAVITY_UNIT 为单位，累计满一行下落一行）
+    fall_progress: u32,
+    /// 左右移动和软降的自动重复状态
+    auto_shift: AutoShift,
+    /// 上一帧的输入（用于判断按键是否刚按下）
+    previous_inputs: Inputs,
+    /// 当前方块生成时的位置（用于判定按键效率）
+    finesse_start: Option<ActivePiece>,
+    /// 当前方块生成后按下的移动和旋转键次数
+    finesse_inputs: u32,
+}
+
+impl Game {
+    /// 使用指定设置和随机种子创建一局新游戏
+    pub fn new(settings: Settings, seed: u64) -> Self {
+        let mut game = Self {
+            board: Board::default(),
+            settings,
+            current_piece: None,
+            next_queue: VecDeque::with_capacity(MAX_PREVIEW_COUNT),
+            hold_piece: None,
+            hold_used: false,
+            score: 0,
+            lines: 0,
+            level: settings.start_level.clamp(1, MAX_START_LEVEL),
+            combo: None,
+            back_to_back: false,
+            callout: None,
+            phase: GamePhase::Entry { remaining: 0 },
+            initial_actions: InitialActions::default(),
+            game_over: false,
+            top_out: None,
+            completed: false,
+            frames: 0,
+            pieces: 0,
+            splits: Vec::new(),
+            finesse_faults: 0,
+            score_breakdown: ScoreBreakdown::default(),
+            seed,
+            rng: Rng::new(seed),
+            randomizer: settings.randomizer.build(),
+            lock_timer: None,
+            locked_spin: None,
+            lock_resets: 0,
+            lowest_row: 0,
+            fall_progress: 0,
+            auto_shift: AutoShift::default(),
+            previous_inputs: Inputs::default(),
+            finesse_start: None,
+            finesse_inputs: 0,
+        };
+        // 使用随机器填满后续方块队列
+        game.fill_queue();
+        game
+    }
+
+    /// 使用新的随机种子重置游戏（用于重新开始游戏，保留当前设置）
+    pub fn reset(&mut self, seed: u64) {
+        *self = Self::new(self.settings, seed);
+    }
+
+    /// 获取本局的随机种子
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// 已进行的时间（毫秒）
+    pub fn elapsed_ms(&self) -> u64 {
+        frames_to_ms(self.frames)
+    }
+
+    /// 每秒放置的方块数（PPS）
+    pub fn pieces_per_second(&self) -> f32 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.pieces as f32 * FRAME_RATE as f32 / self.frames as f32
+    }
+
+    /// 当前方块在两行之间的重力下落进度（0 到 1，用于渲染插值）
+    ///
+    /// 方块触底或重力达到每帧一行以上时返回 0
+    pub fn fall_fraction(&self) -> f32 {
+        let grounded = self
+            .current_piece
+            .is_none_or(|piece| piece.check_collision(0, 1, &self.board));
+        if grounded || gravity(self.level) >= GRAVITY_UNIT {
+            return 0.0;
+        }
+        self.fall_progress as f32 / GRAVITY_UNIT as f32
+    }
+
+    /// 限时模式的剩余时间（帧，None 表示不限时）
+    pub fn remaining_frames(&self) -> Option<u32> {
+        let limit = self.settings.mode.time_limit()?;
+        Some(limit.saturating_sub(self.frames))
+    }
+
+    /// 推进一帧模拟（一帧为 1/FRAME_RATE 秒）
+    /// 按顺序处理：
+    /// 玩家操作：暂存 -> 旋转 -> 平移 -> 软降 -> 硬降
+    /// 下落阶段：方块下落 -> 方块锁定 -> 检查满行并结算分数
+    /// 等待阶段：消行等待结束后消除满行，出块等待结束后生成新方块
+    ///
+    /// 所有计时都按帧计数，相同的输入序列总是得到完全相同的结果
+    ///
+    /// # 参数
+    /// * `inputs` - 本帧按住的操作键
+    pub fn step(&mut self, inputs: Inputs) {
+        // 游戏结束后不再更新
+        if self.game_over {
+            return;
+        }
+        // 限时模式的时间用完时游戏结束
+        if self.remaining_frames() == Some(0) {
+            self.complete();
+            return;
+        }
+        self.frames += 1;
+
+        // 倒计时奖励提示
+        self.tick_callout();
+
+        self.handle_inputs(inputs);
+        self.previous_inputs = inputs;
+
+        match self.phase {
+            GamePhase::Falling => {
+                // 本帧硬降已锁定方块时跳过下落和锁定处理
+                if self.current_piece.is_some() {
+                    self.handle_fall();
+                    self.handle_lock();
+                }
+                // 锁定溢出时游戏立即结束，不再结算消行和生成新方块
+                if self.game_over {
+                    return;
+                }
+                self.handle_clear_lines();
+                // 达成模式目标时游戏立即结束
+                if self.game_over {
+                    return;
+                }
+            }
+            GamePhase::LineClear {
+                ref mut remaining, ..
+            }
+            | GamePhase::Entry { ref mut remaining } => {
+                *remaining = remaining.saturating_sub(1);
+            }
+        }
+
+        // 推进已结束的等待阶段（延迟为 0 时在同一帧内直接推进）
+        self.handle_phase_end();
+    }
+
+    /// 处理玩家操作
+    /// 方块移动按 DAS/ARR 自动重复，软降按软降速度持续下落，
+    /// 旋转、暂存和硬降只在按下的一次触发
+    fn handle_inputs(&mut self, inputs: Inputs) {
+        let pressed = inputs.pressed_since(&self.previous_inputs);
+
+        // 更新自动移位和软降状态（没有活跃方块时 DAS 也会继续蓄力）
+        let shift = self
+            .auto_shift
+            .update_shift(&inputs, &pressed, &self.settings);
+        let soft_drop_rows = self.auto_shift.update_soft_drop(
+            &inputs,
+            &pressed,
+            gravity(self.level),
+            self.settings.soft_drop,
+        );
+
+        // 没有活跃方块时（出块等待、消行等待），记录按住的旋转键和暂存键，
+        // 在下一个方块生成时作为初始旋转/初始暂存（IRS/IHS）应用
+        self.initial_actions = if self.current_piece.is_none() {
+            InitialActions {
+                rotation: inputs.rotation(),
+                hold: inputs.hold,
+            }
+        } else {
+            InitialActions::default()
+        };
+
+        // 暂存
+        if pressed.hold {
+            self.hold();
+        }
+
+        // 统计当前方块的移动和旋转按键次数（按住自动重复只算一次）
+        if self.current_piece.is_some() {
+            let presses = [
+                pressed.left,
+                pressed.right,
+                pressed.rotate_cw,
+                pressed.rotate_ccw,
+                pressed.rotate_180,
+            ];
+            self.finesse_inputs += presses.iter().filter(|&&pressed| pressed).count() as u32;
+        }
+
+        // 旋转：按 SRS 规则旋转，依次尝试踢墙偏移
+        if let Some(direction) = pressed.rotation() {
+            self.rotate_piece(direction);
+        }
+
+        // 水平移动：逐格移动，遇到碰撞即停止
+        for _ in 0..shift.unsigned_abs() {
+            if !self.move_piece(shift.signum(), 0) {
+                break;
+            }
+        }
+
+        // 软降：按软降速度逐行下移
+        for _ in 0..soft_drop_rows {
+            if !self.soft_drop() {
+                break;
+            }
+        }
+
+        // 直接落地：移动到落点位置
+        if pressed.hard_drop {
+            self.hard_drop();
+        }
+    }
+
+    /// 处理方块下落
+    /// 每帧按当前等级的重力累计下落距离，累计满一行下落一行，高等级时一帧内可下落多行
+    fn handle_fall(&mut self) {
+        self.fall_progress += gravity(self.level);
+
+        // 累计距离包含的整行数即为应下落的行数（超过游戏板高度时等同于直接落地）
+        let rows = (self.fall_progress / GRAVITY_UNIT) as usize;
+        self.fall_progress %= GRAVITY_UNIT;
+        if let Some(ref mut piece) = self.current_piece {
+            for _ in 0..rows.min(TOTAL_HEIGHT) {
+                // 检查下移一格是否会碰撞
+                if piece.check_collision(0, 1, &self.board) {
+                    break;
+                }
+                // 没有碰撞，方块下移一格
+                piece.y += 1;
+                piece.last_kick = None;
+            }
+        }
+        self.on_piece_descended();
+    }
+
+    /// 处理方块锁定
+    /// 当方块无法继续下落时，启动延迟计时器，计时结束后将其固定在游戏板上
+    fn handle_lock(&mut self) {
+        let mode = self.settings.lock_delay_mode;
+        // 检查当前方块是否触底（下一步会碰撞）
+        let is_grounded = self
+            .current_piece
+            .is_some_and(|piece| piece.check_collision(0, 1, &self.board));
+
+        if is_grounded {
+            // 方块触底，累加锁定计时器
+            let elapsed = self.lock_timer.unwrap_or(0) + 1;
+            self.lock_timer = Some(elapsed);
+
+            // 计时器超过延迟时间，或移动重置次数已用完，执行锁定
+            let resets_exhausted =
+                mode == LockDelayMode::MoveReset && self.lock_resets >= MAX_LOCK_RESETS;
+            if elapsed >= LOCK_DELAY_FRAMES || resets_exhausted {
+                // 锁定方块，并按溢出规则判定游戏是否结束
+                self.lock_piece();
+            }
+        } else if mode == LockDelayMode::Infinite {
+            // 无限模式下方块离开地面即重置锁定计时器；其他模式下计时暂停，
+            // 避免通过反复离地来绕过重置次数限制
+            self.lock_timer = None;
+        }
+    }
+
+    /// 处理消行
+    /// 方块锁定后检查满行，结算消行和 T-spin 分数，并进入消行等待或出块等待阶段
+    fn handle_clear_lines(&mut self) {
+        // 只在有方块刚锁定时结算
+        let Some(spin) = self.locked_spin.take() else {
+            return;
+        };
+
+        // 根据消行数、T-spin 和全消增加分数（T-spin 不消行也计分），并累计行数
+        let rows = self.board.full_lines();
+        let lines_cleared = rows.len() as u32;
+        let perfect_clear = self.board.is_all_clear(&rows);
+        self.add_score(lines_cleared, spin, perfect_clear);
+        self.add_lines(lines_cleared);
+        if self
+            .settings
+            .mode
+            .line_goal()
+            .is_some_and(|goal| self.lines >= goal)
+        {
+            self.complete();
+            return;
+        }
+
+        // 有满行时先保持显示，等待结束后再消除
+        self.phase = if rows.is_empty() {
+            GamePhase::Entry {
+                remaining: ms_to_frames(self.settings.entry_delay_ms),
+            }
+        } else {
+            GamePhase::LineClear {
+                rows,
+                remaining: ms_to_frames(self.settings.line_clear_delay_ms),
+            }
+        };
+    }
+
+    /// 处理等待阶段的结束
+    /// 消行等待结束后消除满行并进入出块等待，出块等待结束后生成新方块并回到下落阶段
+    fn handle_phase_end(&mut self) {
+        loop {
+            match self.phase {
+                GamePhase::LineClear {
+                    ref rows,
+                    remaining: 0,
+                } => {
+                    // 消除满行，将上面的行下移
+                    self.board.remove_lines(rows);
+                    self.phase = GamePhase::Entry {
+                        remaining: ms_to_frames(self.settings.entry_delay_ms),
+                    };
+                }
+                GamePhase::Entry { remaining: 0 } => {
+                    // 从队列中取出下一个方块（随机器补充队尾）并应用 IRS/IHS，
+                    // 生成位置被占用时游戏结束
+                    self.phase = GamePhase::Falling;
+                    self.spawn_next();
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// 暂存当前方块
+    ///
+    /// 将当前方块放入暂存区，并取出原先暂存的方块（暂存区为空时从队列取下一个方块）。
+    /// 每个方块锁定前只能暂存一次，取出的方块从顶部重新生成
+    ///
+    /// # 返回
+    /// true 表示暂存成功，false 表示本次暂存被拒绝
+    pub fn hold(&mut self) -> bool {
+        if self.hold_used {
+            return false;
+        }
+        let Some(piece) = self.current_piece.take() else {
+            return false;
+        };
+
+        // 取出暂存的方块，暂存区为空时使用下一个方块
+        let incoming = match self.hold_piece.replace(piece.tetromino_type) {
+            Some(held) => held,
+            None => self.pop_next(),
+        };
+
+        self.spawn_piece(incoming, None);
+        self.hold_used = true;
+        true
+    }
+
+    /// 从后续方块队列生成下一个方块，并应用出块等待期间缓存的初始操作
+    ///
+    /// 初始暂存（IHS）：下一个方块直接进入暂存区，改为生成原先暂存的方块；
+    /// 初始旋转（IRS）：方块以旋转后的朝向生成，旋转按踢墙规则检查生成位置
+    ///
+    /// # 返回
+    /// true 表示生成成功，false 表示游戏结束
+    pub fn spawn_next(&mut self) -> bool {
+        let initial = std::mem::take(&mut self.initial_actions);
+        let mut next = self.pop_next();
+        if initial.hold && !self.hold_used {
+            next = match self.hold_piece.replace(next) {
+                Some(held) => held,
+                None => self.pop_next(),
+            };
+            self.hold_used = true;
+        }
+        self.spawn_piece(next, initial.rotation)
+    }
+
+    /// 在顶部生成指定类型的方块并设为当前方块
+    ///
+    /// 方块在可见区域上方的第 21-22 行生成，若下方无阻挡则立即下落一行。
+    /// 指定初始旋转时先尝试按踢墙规则旋转，旋转失败则保持出生朝向。
+    /// 生成位置被占用时：启用方块溢出规则则游戏结束，否则在缓冲区内向上寻找空位，
+    /// 整个缓冲区都没有空位时仍判定为方块溢出
+    ///
+    /// # 返回
+    /// true 表示生成成功，false 表示游戏结束
+    pub fn spawn_piece(
+        &mut self,
+        tetromino_type: TetrominoType,
+        rotation: Option<Rotation>,
+    ) -> bool {
+        let board = &self.board;
+        let mut piece = ActivePiece::new(tetromino_type);
+        if let Some(rotated) = rotation.and_then(|direction| piece.try_rotate(direction, board)) {
+            // 初始旋转不算作玩家的旋转操作，不参与 T-spin 判定
+            piece = ActivePiece {
+                last_kick: None,
+                ..rotated
+            };
+        }
+        if piece.check_collision(0, 0, board) {
+            let raised = (1..=piece.y)
+                .map(|dy| ActivePiece {
+                    y: piece.y - dy,
+                    ..piece
+                })
+                .find(|raised| !raised.check_collision(0, 0, board));
+            match raised {
+                Some(raised) if !self.settings.top_out.block_out => piece = raised,
+                _ => {
+                    self.end_game(TopOutReason::BlockOut);
+                    return false;
+                }
+            }
+        }
+        if !piece.check_collision(0, 1, board) {
+            piece.y += 1;
+        }
+        self.set_active_piece(piece);
+        true
+    }
+
+    /// 将当前方块锁定到游戏板上，并按溢出规则判定游戏是否结束
+    ///
+    /// 锁定前判定 T-spin 交给消行阶段结算。方块总是完整写入游戏板，
+    /// 完全位于可见区域上方时判定为锁定溢出，部分位于上方时判定为部分锁定溢出
+    pub fn lock_piece(&mut self) {
+        let Some(piece) = self.current_piece.take() else {
+            return;
+        };
+        self.locked_spin = Some(piece.t_spin(&self.board));
+        // 按键效率只在冲线模式中统计
+        if matches!(self.settings.mode, GameMode::Sprint { .. }) {
+            self.judge_finesse(&piece);
+        }
+        self.pieces += 1;
+
+        let blocks = piece.blocks();
+        for &(x, y) in &blocks {
+            self.board
+                .set_cell(x as usize, y as usize, Some(piece.tetromino_type));
+        }
+        self.lock_timer = None;
+        // 方块已锁定，下一个方块可以再次使用暂存
+        self.hold_used = false;
+
+        // 统计位于可见区域上方（隐藏缓冲区内）的格子数
+        let above = blocks
+            .iter()
+            .filter(|&&(_, y)| y < BUFFER_HEIGHT as i32)
+            .count();
+        let rules = self.settings.top_out;
+        if above == blocks.len() && rules.lock_out {
+            self.end_game(TopOutReason::LockOut);
+        } else if above > 0 && rules.partial_lock_out {
+            self.end_game(TopOutReason::PartialLockOut);
+        }
+    }
+
+    /// 判定刚锁定方块的按键效率，操作次数多于最少次数时累计失误
+    ///
+    /// 只判定从生成高度直接落下即可到达的落点，软降后滑入或旋入的落点不参与判定
+    fn judge_finesse(&mut self, piece: &ActivePiece) {
+        let Some(start) = self.finesse_start.take() else {
+            return;
+        };
+        let above = ActivePiece {
+            y: start.y,
+            ..*piece
+        };
+        if above.check_collision(0, 0, &self.board) || above.drop_position(&self.board).y != piece.y
+        {
+            return;
+        }
+        if let Some(optimal) = finesse::min_inputs(&start, piece) {
+            self.finesse_faults += self.finesse_inputs.saturating_sub(optimal);
+        }
+    }
+
+    /// 结束游戏并记录原因
+    pub fn end_game(&mut self, reason: TopOutReason) {
+        self.game_over = true;
+        self.top_out = Some(reason);
+    }
+
+    /// 达成模式目标，结束游戏
+    pub fn complete(&mut self) {
+        self.game_over = true;
+        self.completed = true;
+    }
+
+    /// 将新生成的方块设为当前方块，并重置锁定延迟相关状态
+    pub fn set_active_piece(&mut self, piece: ActivePiece) {
+        self.current_piece = Some(piece);
+        self.lock_timer = None;
+        self.lock_resets = 0;
+        self.lowest_row = piece.y;
+        self.finesse_start = Some(piece);
+        self.finesse_inputs = 0;
+    }
+
+    /// 当前方块成功平移或旋转后调用，按锁定延迟模式决定是否重置计时器
+    pub fn on_piece_moved(&mut self) {
+        match self.settings.lock_delay_mode {
+            // 无限模式：任何移动都会重置计时器
+            LockDelayMode::Infinite => self.lock_timer = None,
+            // 移动重置模式：计时开始后的移动才会重置，且每个方块有次数上限
+            LockDelayMode::MoveReset => {
+                if self.lock_timer.is_some() && self.lock_resets < MAX_LOCK_RESETS {
+                    self.lock_timer = None;
+                    self.lock_resets += 1;
+                }
+            }
+            // 步进重置模式：平移和旋转不会重置计时器
+            LockDelayMode::StepReset => {}
+        }
+        self.on_piece_descended();
+    }
+
+    /// 尝试平移或下移当前方块
+    ///
+    /// # 返回
+    /// true 表示移动成功，false 表示没有活跃方块或目标位置发生碰撞
+    pub fn move_piece(&mut self, dx: i32, dy: i32) -> bool {
+        let Some(piece) = self.current_piece else {
+            return false;
+        };
+        if piece.check_collision(dx, dy, &self.board) {
+            return false;
+        }
+
+        self.current_piece = Some(ActivePiece {
+            x: piece.x + dx,
+            y: piece.y + dy,
+            last_kick: None,
+            ..piece
+        });
+        // 下移按下落处理，平移计入移动重置
+        if dy > 0 {
+            self.on_piece_descended();
+        } else {
+            self.on_piece_moved();
+        }
+        true
+    }
+
+    /// 尝试按 SRS 规则旋转当前方块
+    ///
+    /// # 返回
+    /// true 表示旋转成功，false 表示没有活跃方块或所有踢墙偏移均碰撞
+    pub fn rotate_piece(&mut self, direction: Rotation) -> bool {
+        let Some(rotated) = self
+            .current_piece
+            .and_then(|p| p.try_rotate(direction, &self.board))
+        else {
+            return false;
+        };
+        self.current_piece = Some(rotated);
+        self.on_piece_moved();
+        true
+    }
+
+    /// 软降当前方块一行，成功时获得软降分数
+    pub fn soft_drop(&mut self) -> bool {
+        let moved = self.move_piece(0, 1);
+        if moved {
+            self.score += SCORE_SOFT_DROP_CELL;
+            self.score_breakdown.drop += SCORE_SOFT_DROP_CELL;
+        }
+        moved
+    }
+
+    /// 将当前方块直接移动到落点位置并立即锁定（硬降），按下落格数获得硬降分数
+    pub fn hard_drop(&mut self) {
+        if let Some(piece) = self.current_piece {
+            let mut landed = piece.drop_position(&self.board);
+            let points = (landed.y - piece.y) as u32 * SCORE_HARD_DROP_CELL;
+            self.score += points;
+            self.score_breakdown.drop += points;
+            // 实际下落过才算作移动，原地硬降保留旋转信息以便判定 T-spin
+            if landed.y != piece.y {
+                landed.last_kick = None;
+            }
+            self.current_piece = Some(landed);
+            self.lock_piece();
+        }
+    }
+
+    /// 当前方块下落后调用，到达新的最低行时重置计时器和重置次数
+    pub fn on_piece_descended(&mut self) {
+        if let Some(piece) = self.current_piece
+            && piece.y > self.lowest_row
+        {
+            self.lowest_row = piece.y;
+            self.lock_resets = 0;
+            self.lock_timer = None;
+        }
+    }
+
+    /// 使用本局的随机器和随机数生成器生成下一个方块
+    pub fn next_from_randomizer(&mut self) -> TetrominoType {
+        self.randomizer.next(&mut self.rng)
+    }
+
+    /// 从后续方块队列中取出下一个方块，并由随机器补充队尾
+    pub fn pop_next(&mut self) -> TetrominoType {
+        self.fill_queue();
+        let next = self.next_queue.pop_front().unwrap();
+        self.fill_queue();
+        next
+    }
+
+    /// 将后续方块队列补满到 MAX_PREVIEW_COUNT 个
+    fn fill_queue(&mut self) {
+        while self.next_queue.len() < MAX_PREVIEW_COUNT {
+            let next = self.next_from_randomizer();
+            self.next_queue.push_back(next);
+        }
+    }
+
+    /// 累加消行数，并按每 LINES_PER_LEVEL 行升一级更新等级，
+    /// 冲线模式每跨过 SPLIT_LINES 行记录一次分段用时
+    pub fn add_lines(&mut self, lines_cleared: u32) {
+        let previous = self.lines;
+        self.lines += lines_cleared;
+        if matches!(self.settings.mode, GameMode::Sprint { .. }) {
+            for _ in previous / SPLIT_LINES..self.lines / SPLIT_LINES {
+                self.splits.push(self.frames);
+            }
+        }
+        let start_level = self.settings.start_level.clamp(1, MAX_START_LEVEL);
+        self.level = self.level.max(start_level + self.lines / LINES_PER_LEVEL);
+    }
+
+    /// 结算一次方块锁定的分数
+    ///
+    /// 基础分数按消行数和 T-spin 类型计算，并叠加背靠背（×1.5）、连击和全消奖励，
+    /// 最终乘以当前等级。同时生成对应的奖励提示文字，并把各部分分数记入分数明细
+    ///
+    /// # 参数
+    /// * `lines_cleared` - 消行的数量（0-4行，T-spin 不消行也有分数）
+    /// * `spin` - 锁定方块的 T-spin 判定结果
+    /// * `perfect_clear` - 消行后游戏板是否被全部清空
+    pub fn add_score(&mut self, lines_cleared: u32, spin: TSpin, perfect_clear: bool) {
+        let base = Self::base_score(lines_cleared, spin);
+        let mut points = base;
+        let mut callout = Vec::new();
+        let level = self.level;
+
+        // 背靠背：连续的高难度消行额外获得 50% 分数；
+        // 不消行的 T-spin 既不触发也不打断背靠背
+        let difficult = lines_cleared == 4 || (spin != TSpin::None && lines_cleared > 0);
+        let back_to_back = difficult && self.back_to_back;
+        if back_to_back {
+            points = points * 3 / 2;
+            self.score_breakdown.back_to_back += (points - base) * level;
+        }
+        if lines_cleared > 0 {
+            self.back_to_back = difficult;
+        }
+
+        let name = Self::clear_name(lines_cleared, spin);
+        if !name.is_empty() {
+            self.score_breakdown.add_clear(name, base * level);
+        }
+        if difficult || spin != TSpin::None {
+            callout.push(if back_to_back {
+                format!("B2B {name}")
+            } else {
+                name.to_string()
+            });
+        }
+
+        // 连击：连续消行时每级连击奖励 SCORE_COMBO 分，不消行则连击中断
+        if lines_cleared > 0 {
+            let combo = self.combo.map_or(0, |combo| combo + 1);
+            self.combo = Some(combo);
+            if combo > 0 {
+                points += SCORE_COMBO * combo;
+                self.score_breakdown.combo += SCORE_COMBO * combo * level;
+                callout.push(format!("{combo} COMBO"));
+            }
+        } else {
+            self.combo = None;
+        }
+
+        // 全消奖励
+        if perfect_clear && lines_cleared > 0 {
+            let bonus = match lines_cleared {
+                1 => SCORE_PERFECT_CLEAR_1_LINE,
+                2 => SCORE_PERFECT_CLEAR_2_LINES,
+                3 => SCORE_PERFECT_CLEAR_3_LINES,
+                _ if back_to_back => SCORE_PERFECT_CLEAR_B2B_4_LINES,
+                _ => SCORE_PERFECT_CLEAR_4_LINES,
+            };
+            points += bonus;
+            self.score_breakdown.perfect_clear += bonus * level;
+            callout.push("PERFECT CLEAR".to_string());
+        }
+
+        self.score += points * level;
+        if !callout.is_empty() {
+            self.callout = Some(Callout {
+                text: callout.join("\n"),
+                remaining: CALLOUT_FRAMES,
+            });
+        }
+    }
+
+    /// 获取消行类型的显示名称（如 "TETRIS"、"T-SPIN DOUBLE"）
+    pub fn clear_name(lines_cleared: u32, spin: TSpin) -> &'static str {
+        match (spin, lines_cleared) {
+            (TSpin::Full, 0) => "T-SPIN",
+            (TSpin::Full, 1) => "T-SPIN SINGLE",
+            (TSpin::Full, 2) => "T-SPIN DOUBLE",
+            (TSpin::Full, _) => "T-SPIN TRIPLE",
+            (TSpin::Mini, 0) => "MINI T-SPIN",
+            (TSpin::Mini, 1) => "MINI T-SPIN SINGLE",
+            (TSpin::Mini, _) => "MINI T-SPIN DOUBLE",
+            (_, 0) => "",
+            (_, 1) => "SINGLE",
+            (_, 2) => "DOUBLE",
+            (_, 3) => "TRIPLE",
+            _ => "TETRIS",
+        }
+    }
+
+    /// 倒计时奖励提示一帧，显示时间结束后清除
+    pub fn tick_callout(&mut self) {
+        if let Some(ref mut callout) = self.callout {
+            callout.remaining = callout.remaining.saturating_sub(1);
+            if callout.remaining == 0 {
+                self.callout = None;
+            }
+        }
+    }
+
+    /// 按消行数量和 T-spin 类型计算基础分数（未乘等级）
+    fn base_score(lines_cleared: u32, spin: TSpin) -> u32 {
+        match (spin, lines_cleared) {
+            (TSpin::Full, 0) => SCORE_T_SPIN,
+            (TSpin::Full, 1) => SCORE_T_SPIN_SINGLE,
+            (TSpin::Full, 2) => SCORE_T_SPIN_DOUBLE,
+            (TSpin::Full, 3) => SCORE_T_SPIN_TRIPLE,
+            (TSpin::Mini, 0) => SCORE_T_SPIN_MINI,
+            (TSpin::Mini, 1) => SCORE_T_SPIN_MINI_SINGLE,
+            (TSpin::Mini, 2) => SCORE_T_SPIN_MINI_DOUBLE,
+            (_, 1) => SCORE_1_LINE,
+            (_, 2) => SCORE_2_LINES,
+            (_, 3) => SCORE_3_LINES,
+            (_, 4) => SCORE_4_LINES,
+            _ => 0,
+        }
+    }
+}
+
+/// 游戏阶段
+/// 方块锁定后依次经过消行等待和出块等待，再生成下一个方块
+#[derive(Clone, PartialEq, Debug)]
+pub enum GamePhase {
+    /// 方块下落中，玩家可以操作当前方块
+    Falling,
+    /// 消行等待：满行保持显示，等待结束后才消除并下移上方的行
+    LineClear {
+        /// 待消除的满行（从上到下排列）
+        rows: Vec<usize>,
+        /// 剩余等待帧数
+        remaining: u32,
+    },
+    /// 出块等待（ARE）：等待结束后生成下一个方块
+    Entry {
+        /// 剩余等待帧数
+        remaining: u32,
+    },
+}
+
+/// 初始操作
+/// 没有活跃方块时按住的旋转键和暂存键，在下一个方块生成时生效
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct InitialActions {
+    /// 初始旋转（IRS）的方向
+    pub rotation: Option<Rotation>,
+    /// 是否执行初始暂存（IHS）
+    pub hold: bool,
+}
+
+/// 奖励提示
+/// 在消行后短暂显示的文字（如 "B2B TETRIS"、"3 COMBO"、"PERFECT CLEAR"）
+#[derive(Clone, PartialEq, Debug)]
+pub struct Callout {
+    /// 提示文字（多个奖励分行显示）
+    pub text: String,
+    /// 剩余显示帧数
+    pub remaining: u32,
+}
+
+/// 分数明细
+/// 按得分来源拆分本局分数，各项（均已乘等级）之和等于总分
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ScoreBreakdown {
+    /// 各消行类型的次数和基础得分（按首次出现的顺序排列）
+    pub clears: Vec<ClearStat>,
+    /// 背靠背奖励的得分
+    pub back_to_back: u32,
+    /// 连击奖励的得分
+    pub combo: u32,
+    /// 全消奖励的得分
+    pub perfect_clear: u32,
+    /// 软降和硬降的得分
+    pub drop: u32,
+}
+
+impl ScoreBreakdown {
+    /// 记录一次消行（或不消行的 T-spin）及其基础得分
+    fn add_clear(&mut self, name: &'static str, points: u32) {
+        match self.clears.iter_mut().find(|stat| stat.name == name) {
+            Some(stat) => {
+                stat.count += 1;
+                stat.points += points;
+            }
+            None => self.clears.push(ClearStat {
+                name,
+                count: 1,
+                points,
+            }),
+        }
+    }
+}
+
+/// 一种消行类型的统计
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ClearStat {
+    /// 消行类型的显示名称（如 "TETRIS"、"T-SPIN DOUBLE"）
+    pub name: &'static str,
+    /// 次数
+    pub count: u32,
+    /// 基础得分（不含奖励分）
+    pub points: u32,
+}
+
+/// 溢出（游戏结束）原因
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TopOutReason {
+    /// 方块溢出：新方块的生成位置被已锁定的方块占据
+    BlockOut,
+    /// 锁定溢出：方块完全锁定在可见区域上方
+    LockOut,
+    /// 部分锁定溢出：方块有部分格子锁定在可见区域上方
+    PartialLockOut,
+}
+
+impl TopOutReason {
+    /// 获取溢出原因的显示文字
+    pub fn label(&self) -> &'static str {
+        match self {
+            TopOutReason::BlockOut => "BLOCK OUT",
+            TopOutReason::LockOut => "LOCK OUT",
+            TopOutReason::PartialLockOut => "PARTIAL LOCK OUT",
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::random::Rng;
+    use crate::settings::TopOutRules;
+
+    /// 由种子生成的一串随机输入（模拟乱按的玩家）
+    pub(crate) fn random_inputs(seed: u64, frames: usize) -> Vec<Inputs> {
+        let mut rng = Rng::new(seed);
+        (0..frames)
+            .map(|_| Inputs {
+                left: rng.below(4) == 0,
+                right: rng.below(4) == 0,
+                soft_drop: rng.below(3) == 0,
+                hard_drop: rng.below(15) == 0,
+                rotate_cw: rng.below(6) == 0,
+                rotate_ccw: rng.below(8) == 0,
+                rotate_180: rng.below(12) == 0,
+                hold: rng.below(30) == 0,
+            })
+            .collect()
+    }
+
+    fn play(settings: Settings, seed: u64, inputs: &[Inputs]) -> Game {
+        let mut game = Game::new(settings, seed);
+        for &frame in inputs {
+            game.step(frame);
+        }
+        game
+    }
+
+    #[test]
+    fn same_seed_and_inputs_give_same_game() {
+        let inputs = random_inputs(11, 3000);
+        for kind in crate::randomizer::RandomizerKind::ALL {
+            let settings = Settings {
+                randomizer: kind,
+                ..Settings::default()
+            };
+            let a = play(settings, 99, &inputs);
+            let b = play(settings, 99, &inputs);
+            assert!(a.pieces > 10);
+            assert_eq!(a.board, b.board);
+            assert_eq!(a.score, b.score);
+            assert_eq!(a.lines, b.lines);
+            assert_eq!(a.next_queue, b.next_queue);
+            assert_eq!(a.hold_piece, b.hold_piece);
+            assert_eq!(a.frames, b.frames);
+        }
+    }
+
+    #[test]
+    fn reset_replays_the_same_sequence() {
+        let inputs = random_inputs(12, 2000);
+        let mut game = play(Settings::default(), 7, &inputs);
+        let first = (game.board.clone(), game.score);
+        game.reset(7);
+        for &frame in &inputs {
+            game.step(frame);
+        }
+        assert_eq!((game.board.clone(), game.score), first);
+    }
+
+    #[test]
+    fn hard_drop_locks_the_piece_immediately() {
+        let mut game = Game::new(Settings::default(), 3);
+        game.step(Inputs::default());
+        game.step(Inputs {
+            hard_drop: true,
+            ..Inputs::default()
+        });
+        assert!(game.current_piece.is_none());
+        assert_eq!(game.pieces, 1);
+
+        // 出块等待期间按住移动键，已锁定的方块不再移动
+        let board = game.board.clone();
+        for _ in 0..3 {
+            game.step(Inputs {
+                left: true,
+                ..Inputs::default()
+            });
+        }
+        assert_eq!(game.board, board);
+    }
+
+    /// 当前方块已直接落到底部的一局游戏
+    fn grounded_game(lock_delay_mode: LockDelayMode) -> Game {
+        let settings = Settings {
+            lock_delay_mode,
+            ..Settings::default()
+        };
+        let mut game = Game::new(settings, 5);
+        game.step(Inputs::default());
+        let landed = game.current_piece.unwrap().drop_position(&game.board);
+        game.set_active_piece(landed);
+        game
+    }
+
+    /// 每帧交替按左右键，返回方块锁定前经过的帧数（上限 limit 帧）
+    fn frames_until_lock(game: &mut Game, limit: u32) -> u32 {
+        for frame in 1..=limit {
+            game.step(Inputs {
+                left: frame % 2 == 1,
+                right: frame % 2 == 0,
+                ..Inputs::default()
+            });
+            if game.pieces > 0 {
+                return frame;
+            }
+        }
+        limit
+    }
+
+    #[test]
+    fn infinite_lock_delay_never_locks_while_moving() {
+        let mut game = grounded_game(LockDelayMode::Infinite);
+        assert_eq!(frames_until_lock(&mut game, 600), 600);
+        assert_eq!(game.pieces, 0);
+    }
+
+    #[test]
+    fn move_reset_locks_after_fifteen_resets() {
+        let mut game = grounded_game(LockDelayMode::MoveReset);
+        // 第一次移动时计时尚未开始，之后每帧的移动各重置一次
+        assert_eq!(frames_until_lock(&mut game, 600), MAX_LOCK_RESETS + 1);
+    }
+
+    #[test]
+    fn step_reset_ignores_moves() {
+        let mut game = grounded_game(LockDelayMode::StepReset);
+        assert_eq!(frames_until_lock(&mut game, 600), LOCK_DELAY_FRAMES);
+    }
+
+    #[test]
+    fn move_reset_count_clears_on_a_new_lowest_row() {
+        let mut game = grounded_game(LockDelayMode::MoveReset);
+        // 把方块抬高一行，落回最低行前的移动重置次数在到达新的最低行时清零
+        let piece = game.current_piece.unwrap();
+        game.set_active_piece(ActivePiece {
+            y: piece.y - 1,
+            ..piece
+        });
+        game.lock_resets = MAX_LOCK_RESETS - 1;
+        game.move_piece(0, 1);
+        assert_eq!(game.lock_resets, 0);
+        assert_eq!(game.lock_timer, None);
+    }
+
+    fn top_out_game(top_out: TopOutRules) -> Game {
+        let settings = Settings {
+            top_out,
+            ..Settings::default()
+        };
+        Game::new(settings, 1)
+    }
+
+    /// 在指定高度锁定一个 O 方块（O 方块占据 y 和 y + 1 两行）
+    fn lock_o_at(game: &mut Game, y: i32) {
+        let piece = ActivePiece::new(TetrominoType::O);
+        game.set_active_piece(ActivePiece { y, ..piece });
+        game.lock_piece();
+    }
+
+    #[test]
+    fn blocked_spawn_is_a_block_out() {
+        let mut game = top_out_game(TopOutRules::default());
+        game.board.set_cell(4, 19, Some(TetrominoType::I));
+        assert!(!game.spawn_piece(TetrominoType::O, None));
+        assert_eq!(game.top_out, Some(TopOutReason::BlockOut));
+
+        // 关闭方块溢出规则时改为在缓冲区内上移生成
+        let mut game = top_out_game(TopOutRules {
+            block_out: false,
+            ..TopOutRules::default()
+        });
+        game.board.set_cell(4, 19, Some(TetrominoType::I));
+        assert!(game.spawn_piece(TetrominoType::O, None));
+        assert!(!game.game_over);
+        assert_eq!(game.current_piece.unwrap().y, 17);
+    }
+
+    #[test]
+    fn locking_above_the_playfield_is_a_lock_out() {
+        let mut game = top_out_game(TopOutRules::default());
+        lock_o_at(&mut game, 10);
+        assert_eq!(game.top_out, Some(TopOutReason::LockOut));
+
+        let mut game = top_out_game(TopOutRules {
+            lock_out: false,
+            ..TopOutRules::default()
+        });
+        lock_o_at(&mut game, 10);
+        assert!(!game.game_over);
+    }
+
+    #[test]
+    fn partial_lock_out_only_ends_the_game_when_enabled() {
+        // 方块一半位于可见区域上方，默认规则不判定溢出
+        let mut game = top_out_game(TopOutRules::default());
+        lock_o_at(&mut game, BUFFER_HEIGHT as i32 - 1);
+        assert!(!game.game_over);
+
+        let mut game = top_out_game(TopOutRules {
+            partial_lock_out: true,
+            ..TopOutRules::default()
+        });
+        lock_o_at(&mut game, BUFFER_HEIGHT as i32 - 1);
+        assert_eq!(game.top_out, Some(TopOutReason::PartialLockOut));
+    }
+
+    const HARD_DROP: Inputs = Inputs {
+        left: false,
+        right: false,
+        soft_drop: false,
+        hard_drop: true,
+        rotate_cw: false,
+        rotate_ccw: false,
+        rotate_180: false,
+        hold: false,
+    };
+
+    /// 出块等待 6 帧、消行等待 15 帧，且已生成第一个方块的一局游戏
+    fn delayed_game() -> Game {
+        let settings = Settings {
+            entry_delay_ms: 100,
+            line_clear_delay_ms: 250,
+            ..Settings::default()
+        };
+        let mut game = Game::new(settings, 9);
+        game.step(Inputs::default());
+        game
+    }
+
+    /// 按住指定的键不断推进，返回生成下一个方块前经过的帧数
+    fn frames_until_spawn(game: &mut Game, inputs: Inputs) -> u32 {
+        let mut frames = 0;
+        while game.current_piece.is_none() && frames < 600 {
+            game.step(inputs);
+            frames += 1;
+        }
+        frames
+    }
+
+    #[test]
+    fn entry_delay_holds_back_the_next_piece() {
+        let mut game = delayed_game();
+        game.step(HARD_DROP);
+        assert_eq!(game.phase, GamePhase::Entry { remaining: 6 });
+        assert_eq!(frames_until_spawn(&mut game, Inputs::default()), 6);
+        assert_eq!(game.phase, GamePhase::Falling);
+    }
+
+    #[test]
+    fn line_clear_delay_keeps_full_rows_until_it_ends() {
+        let mut game = delayed_game();
+        for y in TOTAL_HEIGHT - 2..TOTAL_HEIGHT {
+            for x in (0..GRID_WIDTH).filter(|&x| x != 4 && x != 5) {
+                game.board.set_cell(x, y, Some(TetrominoType::I));
+            }
+        }
+        game.set_active_piece(ActivePiece::new(TetrominoType::O));
+        game.step(HARD_DROP);
+        assert_eq!(game.lines, 2);
+        assert!(matches!(
+            game.phase,
+            GamePhase::LineClear { remaining: 15, .. }
+        ));
+
+        // 等待期间满行保持显示，结束的一帧消除满行并进入出块等待
+        for _ in 0..14 {
+            game.step(Inputs::default());
+        }
+        assert_eq!(game.board.full_lines().len(), 2);
+        game.step(Inputs::default());
+        assert!(game.board.full_lines().is_empty());
+        assert_eq!(game.phase, GamePhase::Entry { remaining: 6 });
+        assert_eq!(frames_until_spawn(&mut game, Inputs::default()), 6);
+    }
+
+    #[test]
+    fn zero_delays_spawn_in_the_same_frame() {
+        let settings = Settings {
+            entry_delay_ms: 0,
+            line_clear_delay_ms: 0,
+            ..Settings::default()
+        };
+        let mut game = Game::new(settings, 9);
+        game.step(Inputs::default());
+        game.step(HARD_DROP);
+        assert_eq!(game.pieces, 1);
+        assert!(game.current_piece.is_some());
+    }
+
+    #[test]
+    fn rotation_held_during_entry_delay_rotates_the_spawn() {
+        let mut game = delayed_game();
+        game.step(HARD_DROP);
+        let next = game.next_queue[0];
+        let rotate = Inputs {
+            rotate_cw: true,
+            ..Inputs::default()
+        };
+        frames_until_spawn(&mut game, rotate);
+        let piece = game.current_piece.unwrap();
+        assert_eq!(piece.tetromino_type, next);
+        assert_eq!(piece.rotation, 1);
+        // 初始旋转不算作 T-spin 所需的旋转
+        assert_eq!(piece.last_kick, None);
+    }
+
+    #[test]
+    fn hold_held_during_entry_delay_holds_the_spawn() {
+        let mut game = delayed_game();
+        game.step(HARD_DROP);
+        let (next, after) = (game.next_queue[0], game.next_queue[1]);
+        let hold = Inputs {
+            hold: true,
+            ..Inputs::default()
+        };
+        frames_until_spawn(&mut game, hold);
+        assert_eq!(game.current_piece.unwrap().tetromino_type, after);
+        assert_eq!(game.hold_piece, Some(next));
+        assert!(game.hold_used);
+    }
+
+    #[test]
+    fn initial_actions_only_apply_when_held_at_spawn() {
+        let mut game = delayed_game();
+        game.step(HARD_DROP);
+        let next = game.next_queue[0];
+        // 出块等待前半段按住后松开，生成时不再应用
+        for _ in 0..3 {
+            game.step(Inputs {
+                rotate_cw: true,
+                hold: true,
+                ..Inputs::default()
+            });
+        }
+        frames_until_spawn(&mut game, Inputs::default());
+        let piece = game.current_piece.unwrap();
+        assert_eq!((piece.tetromino_type, piece.rotation), (next, 0));
+        assert_eq!(game.hold_piece, None);
+    }
+
+    #[test]
+    fn finesse_is_only_judged_in_sprint() {
+        let wiggle = |mode: GameMode| {
+            let settings = Settings {
+                mode,
+                ..Settings::default()
+            };
+            let mut game = Game::new(settings, 4);
+            game.step(Inputs::default());
+            // 左右来回各按两次后在原地硬降，最少操作次数为 0
+            for frame in 0..4 {
+                game.step(Inputs {
+                    left: frame % 2 == 0,
+                    right: frame % 2 == 1,
+                    ..Inputs::default()
+                });
+            }
+            game.step(HARD_DROP);
+            game.finesse_faults
+        };
+        assert_eq!(wiggle(GameMode::Sprint { lines: 40 }), 4);
+        assert_eq!(wiggle(GameMode::default()), 0);
+    }
+
+    #[test]
+    fn score_breakdown_adds_up_to_score() {
+        let game = play(Settings::default(), 3, &random_inputs(13, 5000));
+        let breakdown = &game.score_breakdown;
+        let clears: u32 = breakdown.clears.iter().map(|clear| clear.points).sum();
+        let total = clears
+            + breakdown.back_to_back
+            + breakdown.combo
+            + breakdown.perfect_clear
+            + breakdown.drop;
+        assert_eq!(total, game.score);
+    }
+
+    #[test]
+    fn ultra_ends_when_time_runs_out() {
+        let settings = Settings {
+            mode: GameMode::Ultra { seconds: 10 },
+            ..Settings::default()
+        };
+        let mut game = Game::new(settings, 1);
+        while !game.game_over {
+            game.step(Inputs::default());
+        }
+        assert!(game.completed);
+        assert_eq!(game.frames, 10 * FRAME_RATE);
+        assert_eq!(game.remaining_frames(), Some(0));
+    }
+
+    #[test]
+    fn start_level_sets_the_initial_level() {
+        let settings = Settings {
+            start_level: 5,
+            ..Settings::default()
+        };
+        let mut game = Game::new(settings, 1);
+        assert_eq!(game.level, 5);
+        game.add_lines(LINES_PER_LEVEL);
+        assert_eq!(game.level, 6);
+    }
+
+    #[test]
+    fn splits_are_only_recorded_in_sprint() {
+        let sprint = Settings {
+            mode: GameMode::Sprint { lines: 40 },
+            ..Settings::default()
+        };
+        let mut game = Game::new(sprint, 1);
+        game.add_lines(4);
+        game.add_lines(8);
+        game.add_lines(10);
+        assert_eq!(game.splits.len(), 2);
+
+        let mut game = Game::new(Settings::default(), 1);
+        game.add_lines(40);
+        assert!(game.splits.is_empty());
+    }
+}
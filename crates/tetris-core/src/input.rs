@@ -0,0 +1,305 @@
+// 输入模块：玩家操作状态和 DAS/ARR 自动重复
+
+use crate::constants::*;
+use crate::settings::{Settings, SoftDropSpeed};
+use crate::tetromino::Rotation;
+
+/// 玩家输入
+/// 记录某一时刻各个操作键是否处于按住状态，与具体的输入设备无关。
+/// 按下和松开的时机由模拟核心对比前后两帧的输入得出
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Inputs {
+    /// 左移
+    pub left: bool,
+    /// 右移
+    pub right: bool,
+    /// 软降
+    pub soft_drop: bool,
+    /// 硬降
+    pub hard_drop: bool,
+    /// 顺时针旋转
+    pub rotate_cw: bool,
+    /// 逆时针旋转
+    pub rotate_ccw: bool,
+    /// 180° 旋转
+    pub rotate_180: bool,
+    /// 暂存
+    pub hold: bool,
+}
+
+impl Inputs {
+    /// 获取按住的旋转键对应的旋转方向（同时按住多个时顺时针优先）
+    pub fn rotation(&self) -> Option<Rotation> {
+        if self.rotate_cw {
+            Some(Rotation::Clockwise)
+        } else if self.rotate_ccw {
+            Some(Rotation::CounterClockwise)
+        } else if self.rotate_180 {
+            Some(Rotation::Half)
+        } else {
+            None
+        }
+    }
+
+    /// 合并两次输入（任意一次按住即视为按住）
+    pub fn union(&self, other: &Inputs) -> Inputs {
+        Inputs {
+            left: self.left || other.left,
+            right: self.right || other.right,
+            soft_drop: self.soft_drop || other.soft_drop,
+            hard_drop: self.hard_drop || other.hard_drop,
+            rotate_cw: self.rotate_cw || other.rotate_cw,
+            rotate_ccw: self.rotate_ccw || other.rotate_ccw,
+            rotate_180: self.rotate_180 || other.rotate_180,
+            hold: self.hold || other.hold,
+        }
+    }
+
+    /// 获取相对上一次输入新按下的键
+    pub fn pressed_since(&self, previous: &Inputs) -> Inputs {
+        Inputs {
+            left: self.left && !previous.left,
+            right: self.right && !previous.right,
+            soft_drop: self.soft_drop && !previous.soft_drop,
+            hard_drop: self.hard_drop && !previous.hard_drop,
+            rotate_cw: self.rotate_cw && !previous.rotate_cw,
+            rotate_ccw: self.rotate_ccw && !previous.rotate_ccw,
+            rotate_180: self.rotate_180 && !previous.rotate_180,
+            hold: self.hold && !previous.hold,
+        }
+    }
+}
+
+/// 自动移位输入状态
+/// 记录左右键和下键的按住帧数，实现 DAS/ARR 自动重复和软降
+#[derive(Clone, Default, Debug)]
+pub struct AutoShift {
+    /// 当前生效的水平方向（-1 左，1 右，0 无）
+    direction: i32,
+    /// 当前方向键已按住的帧数，达到 DAS 后开始自动重复（蓄满后不再增加）
+    charge: u32,
+    /// 距上一次自动重复移动经过的帧数
+    repeat: u32,
+    /// 软降累计的下落距离（以 GRAVITY_UNIT 为单位）
+    soft_drop: u32,
+}
+
+impl AutoShift {
+    /// 更新一帧水平方向的自动移位状态
+    ///
+    /// 后按下的方向键优先；松开当前方向键时切换到仍按住的另一方向。
+    /// 蓄力与是否存在活跃方块无关，因此出块等待期间也能提前蓄满 DAS
+    ///
+    /// # 参数
+    /// * `held` - 本帧按住的键
+    /// * `pressed` - 本帧新按下的键
+    ///
+    /// # 返回
+    /// 本帧应移动的格数（带方向，负数向左）
+    pub fn update_shift(&mut self, held: &Inputs, pressed: &Inputs, settings: &Settings) -> i32 {
+        // 判断方向是否发生变化（新按下或松开当前方向）
+        let new_direction = if pressed.left {
+            Some(-1)
+        } else if pressed.right {
+            Some(1)
+        } else if (self.direction == -1 && !held.left) || (self.direction == 1 && !held.right) {
+            Some(if held.left {
+                -1
+            } else if held.right {
+                1
+            } else {
+                0
+            })
+        } else {
+            None
+        };
+
+        if let Some(direction) = new_direction {
+            // 方向改变时重新蓄力，并立即移动一格
+            self.direction = direction;
+            self.charge = 0;
+            self.repeat = 0;
+            return direction;
+        }
+        if self.direction == 0 {
+            return 0;
+        }
+
+        let das = ms_to_frames(settings.das_ms);
+        let arr = ms_to_frames(settings.arr_ms);
+        // ARR 为 0 时瞬间移到墙边
+        let repeat_step = if arr == 0 {
+            self.direction * GRID_WIDTH as i32
+        } else {
+            self.direction
+        };
+
+        if self.charge < das {
+            self.charge += 1;
+            if self.charge < das {
+                return 0;
+            }
+            // 刚蓄满的一帧立即移动，之后按 ARR 间隔重复
+            self.repeat = 0;
+            return repeat_step;
+        }
+        if arr == 0 {
+            return repeat_step;
+        }
+        self.repeat += 1;
+        if self.repeat >= arr {
+            self.repeat = 0;
+            repeat_step
+        } else {
+            0
+        }
+    }
+
+    /// 更新一帧软降状态
+    ///
+    /// # 参数
+    /// * `held` - 本帧按住的键
+    /// * `pressed` - 本帧新按下的键
+    /// * `gravity` - 当前等级下每帧的重力（以 GRAVITY_UNIT 为单位）
+    ///
+    /// # 返回
+    /// 本帧应软降的行数
+    pub fn update_soft_drop(
+        &mut self,
+        held: &Inputs,
+        pressed: &Inputs,
+        gravity: u32,
+        speed: SoftDropSpeed,
+    ) -> usize {
+        if !held.soft_drop {
+            self.soft_drop = 0;
+            return 0;
+        }
+
+        match speed {
+            SoftDropSpeed::Instant => TOTAL_HEIGHT,
+            SoftDropSpeed::Factor(factor) => {
+                // 刚按下时立即下移一行
+                if pressed.soft_drop {
+                    self.soft_drop = 0;
+                    return 1;
+                }
+                self.soft_drop = self
+                    .soft_drop
+                    .saturating_add(gravity.saturating_mul(factor.max(1)));
+                let rows = self.soft_drop / GRAVITY_UNIT;
+                self.soft_drop %= GRAVITY_UNIT;
+                (rows as usize).min(TOTAL_HEIGHT)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按帧依次输入，返回每帧自动移位得到的移动格数
+    fn shifts(settings: &Settings, frames: &[Inputs]) -> Vec<i32> {
+        let mut auto_shift = AutoShift::default();
+        let mut previous = Inputs::default();
+        frames
+            .iter()
+            .map(|held| {
+                let pressed = held.pressed_since(&previous);
+                previous = *held;
+                auto_shift.update_shift(held, &pressed, settings)
+            })
+            .collect()
+    }
+
+    const LEFT: Inputs = Inputs {
+        left: true,
+        right: false,
+        soft_drop: false,
+        hard_drop: false,
+        rotate_cw: false,
+        rotate_ccw: false,
+        rotate_180: false,
+        hold: false,
+    };
+
+    const RIGHT: Inputs = Inputs {
+        left: false,
+        right: true,
+        ..LEFT
+    };
+
+    const BOTH: Inputs = Inputs {
+        left: true,
+        ..RIGHT
+    };
+
+    #[test]
+    fn das_delays_the_repeat_and_arr_spaces_it() {
+        let settings = Settings {
+            das_ms: 100,
+            arr_ms: 50,
+            ..Settings::default()
+        };
+        // 按下立即移动一格，第 6 帧蓄满 DAS 后每 3 帧重复一次
+        assert_eq!(
+            shifts(&settings, &[RIGHT; 13]),
+            [1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn zero_arr_shifts_to_the_wall() {
+        let settings = Settings {
+            das_ms: 50,
+            arr_ms: 0,
+            ..Settings::default()
+        };
+        let moves = shifts(&settings, &[LEFT; 5]);
+        assert_eq!(moves[..3], [-1, 0, 0]);
+        assert!(moves[3..].iter().all(|&dx| dx == -(GRID_WIDTH as i32)));
+    }
+
+    #[test]
+    fn releasing_a_key_restarts_the_charge() {
+        let settings = Settings {
+            das_ms: 50,
+            arr_ms: 50,
+            ..Settings::default()
+        };
+        let none = Inputs::default();
+        assert_eq!(
+            shifts(&settings, &[RIGHT, RIGHT, none, RIGHT, RIGHT, RIGHT, RIGHT]),
+            [1, 0, 0, 1, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn last_pressed_direction_wins() {
+        let settings = Settings::default();
+        // 按住左键时再按右键改为向右，松开右键后回到仍按住的左键
+        assert_eq!(shifts(&settings, &[LEFT, BOTH, LEFT]), [-1, 1, -1]);
+    }
+
+    #[test]
+    fn soft_drop_moves_at_a_multiple_of_gravity() {
+        let held = Inputs {
+            soft_drop: true,
+            ..Inputs::default()
+        };
+        let mut auto_shift = AutoShift::default();
+        let speed = SoftDropSpeed::Factor(4);
+        // 刚按下时立即下移一行，之后以 4 倍重力（每帧半行）下落
+        let gravity = GRAVITY_UNIT / 8;
+        assert_eq!(auto_shift.update_soft_drop(&held, &held, gravity, speed), 1);
+        let rows: Vec<usize> = (0..4)
+            .map(|_| auto_shift.update_soft_drop(&held, &Inputs::default(), gravity, speed))
+            .collect();
+        assert_eq!(rows, [0, 1, 0, 1]);
+        assert_eq!(
+            auto_shift.update_soft_drop(&held, &held, gravity, SoftDropSpeed::Instant),
+            TOTAL_HEIGHT
+        );
+    }
+}
@@ -0,0 +1,24 @@
+// 俄罗斯方块游戏核心
+// 不依赖任何游戏引擎的纯逻辑模拟，可用于图形前端、测试、机器人和服务器
+
+pub mod board;
+pub mod constants;
+pub mod finesse;
+pub mod game;
+pub mod input;
+pub mod mode;
+pub mod random;
+pub mod randomizer;
+pub mod replay;
+pub mod settings;
+pub mod tetromino;
+
+pub use board::*;
+pub use game::*;
+pub use input::*;
+pub use mode::*;
+pub use random::*;
+pub use randomizer::*;
+pub use replay::*;
+pub use settings::*;
+pub use tetromino::*;
@@ -0,0 +1,130 @@
+// 游戏模式模块：各模式的目标和结束条件
+
+use crate::constants::{FRAME_RATE, MARATHON_LINES};
+
+/// 游戏模式
+/// 决定一局游戏的目标和结束条件，回放中记录模式以复现结束时机
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameMode {
+    /// 马拉松模式：消除 MARATHON_LINES 行即通关，每消除 LINES_PER_LEVEL 行升一级（默认模式）
+    Marathon {
+        /// 是否为无尽变体（没有行数目标，一直玩到溢出为止）
+        endless: bool,
+    },
+    /// 冲线模式：以最短时间消除指定的行数
+    Sprint {
+        /// 目标行数
+        lines: u32,
+    },
+    /// 限时模式：在限定时间内取得尽可能高的分数
+    Ultra {
+        /// 限定时间（秒）
+        seconds: u32,
+    },
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        GameMode::Marathon { endless: false }
+    }
+}
+
+impl GameMode {
+    /// 冲线模式可选的目标行数
+    pub const SPRINT_LINES: [u32; 3] = [20, 40, 100];
+    /// 限时模式可选的限定时间（秒）
+    pub const ULTRA_SECONDS: [u32; 4] = [60, 120, 180, 300];
+    /// 限时模式允许的最长限定时间（秒），按名称解析时超过该值视为无效
+    pub const MAX_ULTRA_SECONDS: u32 = 60 * 60;
+
+    /// 获取模式的名称（用于命令行参数、回放和最高分文件，如 "sprint40"）
+    pub fn name(&self) -> String {
+        match self {
+            GameMode::Marathon { endless: false } => "marathon".to_string(),
+            GameMode::Marathon { endless: true } => "marathon-endless".to_string(),
+            GameMode::Sprint { lines } => format!("sprint{lines}"),
+            GameMode::Ultra { seconds } => format!("ultra{seconds}"),
+        }
+    }
+
+    /// 根据名称获取模式
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "marathon" => return Some(GameMode::Marathon { endless: false }),
+            "marathon-endless" => return Some(GameMode::Marathon { endless: true }),
+            _ => {}
+        }
+        if let Some(seconds) = name.strip_prefix("ultra") {
+            let seconds = seconds.parse().ok()?;
+            return (1..=Self::MAX_ULTRA_SECONDS)
+                .contains(&seconds)
+                .then_some(GameMode::Ultra { seconds });
+        }
+        let lines = name.strip_prefix("sprint")?.parse().ok()?;
+        (lines > 0).then_some(GameMode::Sprint { lines })
+    }
+
+    /// 获取模式的显示名称（如 "SPRINT 40L"、"ULTRA 2:00"）
+    pub fn label(&self) -> String {
+        match self {
+            GameMode::Marathon { endless: false } => format!("MARATHON {MARATHON_LINES}L"),
+            GameMode::Marathon { endless: true } => "MARATHON ENDLESS".to_string(),
+            GameMode::Sprint { lines } => format!("SPRINT {lines}L"),
+            GameMode::Ultra { seconds } => format!("ULTRA {}:{:02}", seconds / 60, seconds % 60),
+        }
+    }
+
+    /// 达成目标所需消除的行数（None 表示没有行数目标）
+    pub fn line_goal(&self) -> Option<u32> {
+        match self {
+            GameMode::Marathon { endless: false } => Some(MARATHON_LINES),
+            GameMode::Marathon { endless: true } | GameMode::Ultra { .. } => None,
+            GameMode::Sprint { lines } => Some(*lines),
+        }
+    }
+
+    /// 是否只记录达成目标的成绩（冲线和限时模式按目标比较成绩，马拉松模式溢出时也按分数记录）
+    pub fn requires_completion(&self) -> bool {
+        matches!(self, GameMode::Sprint { .. } | GameMode::Ultra { .. })
+    }
+
+    /// 限定的游戏时间（帧，None 表示不限时）
+    pub fn time_limit(&self) -> Option<u32> {
+        match self {
+            GameMode::Ultra { seconds } => Some(seconds.saturating_mul(FRAME_RATE)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_names_round_trip() {
+        let modes = [
+            GameMode::Marathon { endless: false },
+            GameMode::Marathon { endless: true },
+            GameMode::Sprint { lines: 40 },
+            GameMode::Ultra { seconds: 120 },
+        ];
+        for mode in modes {
+            assert_eq!(GameMode::from_name(&mode.name()), Some(mode));
+        }
+        assert_eq!(GameMode::from_name("sprint0"), None);
+        assert_eq!(GameMode::from_name("endless"), None);
+    }
+
+    #[test]
+    fn ultra_time_limit_is_bounded() {
+        let longest = format!("ultra{}", GameMode::MAX_ULTRA_SECONDS);
+        assert!(GameMode::from_name(&longest).is_some());
+        let too_long = format!("ultra{}", GameMode::MAX_ULTRA_SECONDS + 1);
+        assert_eq!(GameMode::from_name(&too_long), None);
+        assert_eq!(GameMode::from_name("ultra0"), None);
+
+        let mode = GameMode::Ultra { seconds: u32::MAX };
+        assert_eq!(mode.time_limit(), Some(u32::MAX));
+    }
+}
@@ -0,0 +1,50 @@
+// 随机数模块：可设置种子的确定性伪随机数生成器
+
+/// 伪随机数生成器（SplitMix64 算法）
+///
+/// 只使用整数运算，相同种子在所有平台上都产生完全相同的随机序列，
+/// 用于保证方块序列可以复现（回放、每日挑战和公平对战）
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rng {
+    /// 内部状态
+    state: u64,
+}
+
+impl Rng {
+    /// 使用指定种子创建随机数生成器
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// 生成下一个 64 位随机数
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 生成 [0, n) 范围内均匀分布的随机整数
+    ///
+    /// 丢弃会导致取模偏差的尾部结果，保证每个值出现的概率完全相同
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "随机数范围不能为空");
+        let n = n as u64;
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % n) as usize;
+            }
+        }
+    }
+
+    /// 原地随机打乱切片（Fisher-Yates 洗牌算法）
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
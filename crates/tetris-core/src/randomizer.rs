@@ -0,0 +1,340 @@
+// 随机器模块：决定方块出现顺序的各种随机规则
+
+use crate::random::Rng;
+use crate::tetromino::TetrominoType;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// 方块随机器
+/// 按各自的规则从随机数生成器产生方块序列，随机性全部来自传入的生成器，
+/// 因此相同种子总是产生相同的序列
+pub trait Randomizer: Debug + Send + Sync {
+    /// 生成下一个方块
+    fn next(&mut self, rng: &mut Rng) -> TetrominoType;
+
+    /// 复制当前随机器（包括内部状态）
+    fn clone_box(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// 随机器类型
+/// 用于在游戏设置中选择随机规则
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RandomizerKind {
+    /// 7-Bag：每 7 个方块包含全部 7 种各一个
+    SevenBag,
+    /// 14-Bag：每 14 个方块包含全部 7 种各两个
+    FourteenBag,
+    /// 完全随机：每个方块独立随机
+    PureRandom,
+    /// NES 重抽：与上一个方块相同时重抽一次
+    NesReroll,
+    /// TGM 历史记录：避开最近 4 个方块，最多抽 `rolls` 次
+    TgmHistory {
+        /// 最多抽取的次数（TGM 为 4 次，TGM2 为 6 次）
+        rolls: u32,
+    },
+    /// 7-Bag+1：每袋在 7 种方块之外额外加入 1 个随机方块
+    BagPlusOne,
+}
+
+impl RandomizerKind {
+    /// 所有可选的随机器类型
+    pub const ALL: [RandomizerKind; 7] = [
+        RandomizerKind::SevenBag,
+        RandomizerKind::FourteenBag,
+        RandomizerKind::PureRandom,
+        RandomizerKind::NesReroll,
+        RandomizerKind::TgmHistory { rolls: 4 },
+        RandomizerKind::TgmHistory { rolls: 6 },
+        RandomizerKind::BagPlusOne,
+    ];
+
+    /// 获取随机器类型的名称（用于显示和命令行参数）
+    pub fn name(&self) -> String {
+        match self {
+            RandomizerKind::SevenBag => "7bag".to_string(),
+            RandomizerKind::FourteenBag => "14bag".to_string(),
+            RandomizerKind::PureRandom => "random".to_string(),
+            RandomizerKind::NesReroll => "nes".to_string(),
+            RandomizerKind::TgmHistory { rolls } => format!("tgm{rolls}"),
+            RandomizerKind::BagPlusOne => "bag+1".to_string(),
+        }
+    }
+
+    /// 根据名称查找随机器类型
+    ///
+    /// # 返回
+    /// 名称对应的随机器类型，未知名称返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// 创建该类型的随机器
+    pub fn build(&self) -> Box<dyn Randomizer> {
+        match *self {
+            RandomizerKind::SevenBag => Box::new(BagRandomizer::new(1, 0)),
+            RandomizerKind::FourteenBag => Box::new(BagRandomizer::new(2, 0)),
+            RandomizerKind::PureRandom => Box::new(PureRandomizer),
+            RandomizerKind::NesReroll => Box::new(NesRandomizer::default()),
+            RandomizerKind::TgmHistory { rolls } => Box::new(HistoryRandomizer::new(rolls)),
+            RandomizerKind::BagPlusOne => Box::new(BagRandomizer::new(1, 1)),
+        }
+    }
+}
+
+/// 袋子随机器
+///
+/// 每袋放入全部 7 种方块各 `copies` 个，再加上 `extra` 个随机方块，
+/// 打乱后依次发放，发完后重新装袋
+#[derive(Clone, Debug)]
+pub struct BagRandomizer {
+    /// 每袋中每种方块的数量
+    copies: usize,
+    /// 每袋额外加入的随机方块数量
+    extra: usize,
+    /// 袋子中待发放的方块
+    bag: Vec<TetrominoType>,
+}
+
+impl BagRandomizer {
+    /// 创建袋子随机器
+    pub fn new(copies: usize, extra: usize) -> Self {
+        Self {
+            copies,
+            extra,
+            bag: Vec::new(),
+        }
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoType {
+        // 如果袋子空了，重新装袋并打乱
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend(TetrominoType::ALL);
+            }
+            for _ in 0..self.extra {
+                self.bag.push(TetrominoType::random(rng));
+            }
+            rng.shuffle(&mut self.bag);
+        }
+        self.bag.pop().unwrap()
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// 完全随机器
+/// 每个方块独立地从 7 种中等概率抽取
+#[derive(Clone, Debug)]
+pub struct PureRandomizer;
+
+impl Randomizer for PureRandomizer {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoType {
+        TetrominoType::random(rng)
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// NES 重抽随机器
+///
+/// 先从 8 个结果中抽取（第 8 个表示重抽），抽到重抽或与上一个方块相同时，
+/// 再从 7 种方块中重抽一次并直接采用
+#[derive(Clone, Default, Debug)]
+pub struct NesRandomizer {
+    /// 上一个发放的方块
+    last: Option<TetrominoType>,
+}
+
+impl Randomizer for NesRandomizer {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoType {
+        let roll = rng.below(TetrominoType::ALL.len() + 1);
+        let piece = match TetrominoType::ALL.get(roll) {
+            Some(&piece) if Some(piece) != self.last => piece,
+            _ => TetrominoType::random(rng),
+        };
+        self.last = Some(piece);
+        piece
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// TGM 历史记录随机器
+///
+/// 记录最近发放的 4 个方块，最多抽取 `rolls` 次，抽到不在历史记录中的方块即采用，
+/// 否则采用最后一次的结果。第一个方块不会是 S、Z 或 O。
+/// 4 次抽取时历史记录初始为 Z Z Z Z（TGM），6 次时为 Z S S Z（TGM2）
+#[derive(Clone, Debug)]
+pub struct HistoryRandomizer {
+    /// 最多抽取的次数
+    rolls: u32,
+    /// 最近发放的 4 个方块
+    history: VecDeque<TetrominoType>,
+    /// 是否还没有发放过方块
+    first: bool,
+}
+
+impl HistoryRandomizer {
+    /// 历史记录长度
+    const HISTORY_LEN: usize = 4;
+
+    /// 第一个方块的候选（避免开局出现 S、Z、O）
+    const FIRST_PIECES: [TetrominoType; 4] = [
+        TetrominoType::I,
+        TetrominoType::J,
+        TetrominoType::L,
+        TetrominoType::T,
+    ];
+
+    /// 创建历史记录随机器
+    pub fn new(rolls: u32) -> Self {
+        use TetrominoType::{S, Z};
+        let history = if rolls >= 6 {
+            [Z, S, S, Z]
+        } else {
+            [Z, Z, Z, Z]
+        };
+        Self {
+            rolls: rolls.max(1),
+            history: history.into(),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for HistoryRandomizer {
+    fn next(&mut self, rng: &mut Rng) -> TetrominoType {
+        let piece = if self.first {
+            self.first = false;
+            Self::FIRST_PIECES[rng.below(Self::FIRST_PIECES.len())]
+        } else {
+            let mut piece = TetrominoType::random(rng);
+            for _ in 1..self.rolls {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = TetrominoType::random(rng);
+            }
+            piece
+        };
+
+        // 更新历史记录，只保留最近的 HISTORY_LEN 个方块
+        self.history.push_back(piece);
+        if self.history.len() > Self::HISTORY_LEN {
+            self.history.pop_front();
+        }
+        piece
+    }
+
+    fn clone_box(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 用指定种子生成一段方块序列
+    fn sequence(kind: RandomizerKind, seed: u64, len: usize) -> Vec<TetrominoType> {
+        let mut rng = Rng::new(seed);
+        let mut randomizer = kind.build();
+        (0..len).map(|_| randomizer.next(&mut rng)).collect()
+    }
+
+    /// 统计每种方块出现的次数（按 TetrominoType::ALL 的顺序）
+    fn counts(pieces: &[TetrominoType]) -> [usize; 7] {
+        TetrominoType::ALL.map(|kind| pieces.iter().filter(|&&piece| piece == kind).count())
+    }
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        for kind in RandomizerKind::ALL {
+            assert_eq!(sequence(kind, 42, 500), sequence(kind, 42, 500));
+        }
+    }
+
+    #[test]
+    fn seven_bag_deals_every_piece_once_per_bag() {
+        for bag in sequence(RandomizerKind::SevenBag, 1, 7 * 100).chunks(7) {
+            assert_eq!(counts(bag), [1; 7]);
+        }
+    }
+
+    #[test]
+    fn fourteen_bag_deals_every_piece_twice_per_bag() {
+        for bag in sequence(RandomizerKind::FourteenBag, 2, 14 * 50).chunks(14) {
+            assert_eq!(counts(bag), [2; 7]);
+        }
+    }
+
+    #[test]
+    fn bag_plus_one_adds_one_extra_piece_per_bag() {
+        for bag in sequence(RandomizerKind::BagPlusOne, 3, 8 * 100).chunks(8) {
+            let counts = counts(bag);
+            assert!(counts.iter().all(|&count| count >= 1));
+            assert_eq!(counts.iter().sum::<usize>(), 8);
+        }
+    }
+
+    #[test]
+    fn nes_reroll_makes_repeats_rare() {
+        let pieces = sequence(RandomizerKind::NesReroll, 4, 7000);
+        let repeats = pieces.windows(2).filter(|pair| pair[0] == pair[1]).count();
+        // 完全随机时约 1/7 的方块与上一个相同，重抽后约为 1/28
+        assert!(repeats < pieces.len() / 14, "repeats: {repeats}");
+        assert!(counts(&pieces).iter().all(|&count| count > 0));
+    }
+
+    #[test]
+    fn history_never_starts_with_s_z_or_o() {
+        for rolls in [4, 6] {
+            for seed in 0..200 {
+                let first = sequence(RandomizerKind::TgmHistory { rolls }, seed, 1)[0];
+                assert!(HistoryRandomizer::FIRST_PIECES.contains(&first));
+            }
+        }
+    }
+
+    #[test]
+    fn history_avoids_recent_pieces() {
+        let pure = sequence(RandomizerKind::PureRandom, 5, 7000);
+        let recent = |pieces: &[TetrominoType]| {
+            (4..pieces.len())
+                .filter(|&i| pieces[i - 4..i].contains(&pieces[i]))
+                .count()
+        };
+        let pure_recent = recent(&pure);
+        let mut previous = pure_recent;
+        // 抽取次数越多，与最近 4 个方块重复的情况越少
+        for rolls in [4, 6] {
+            let pieces = sequence(RandomizerKind::TgmHistory { rolls }, 5, 7000);
+            let history_recent = recent(&pieces);
+            assert!(history_recent < previous, "rolls {rolls}: {history_recent}");
+            previous = history_recent;
+        }
+        assert!(previous < pure_recent / 4);
+    }
+
+    #[test]
+    fn randomizer_names_round_trip() {
+        for kind in RandomizerKind::ALL {
+            assert_eq!(RandomizerKind::from_name(&kind.name()), Some(kind));
+        }
+    }
+}
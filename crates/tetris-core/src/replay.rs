@@ -0,0 +1,478 @@
+// 回放模块：录制一局游戏的输入，并按录制内容重新模拟
+
+use crate::constants::FRAME_RATE;
+use crate::game::Game;
+use crate::input::Inputs;
+use crate::mode::GameMode;
+use crate::randomizer::RandomizerKind;
+use crate::settings::{LockDelayMode, Settings, SoftDropSpeed, TopOutRules};
+use std::fmt;
+
+/// 回放文件的格式标识（第一行）
+const REPLAY_HEADER: &str = "tetris-replay 1";
+
+/// 回放最多包含的帧数（10 小时），超过时视为损坏的文件，避免解析时分配过多内存
+pub const MAX_REPLAY_FRAMES: usize = FRAME_RATE as usize * 60 * 60 * 10;
+
+/// 回放中计时设置（DAS、ARR、出块等待和消行等待）允许的最大值（毫秒），
+/// 超过时视为损坏的文件
+pub const MAX_REPLAY_TIMING_MS: u32 = 60_000;
+
+/// 输入标志的字符表示，顺序依次为：左移、右移、软降、硬降、顺时针、逆时针、180°、暂存
+const INPUT_FLAGS: [char; 8] = ['L', 'R', 'D', 'H', 'X', 'Z', 'A', 'C'];
+
+/// 回放
+/// 记录一局游戏的随机种子、设置和每帧的输入。
+/// 模拟核心是确定性的，用相同的种子和设置依次重放这些输入即可完全复现整局游戏
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replay {
+    /// 随机种子
+    pub seed: u64,
+    /// 游戏设置
+    pub settings: Settings,
+    /// 每帧按住的操作键（按时间顺序排列）
+    pub frames: Vec<Inputs>,
+}
+
+impl Replay {
+    /// 为一局新游戏创建空的回放
+    pub fn new(seed: u64, settings: Settings) -> Self {
+        Self {
+            seed,
+            settings,
+            frames: Vec::new(),
+        }
+    }
+
+    /// 记录一帧的输入
+    pub fn record(&mut self, inputs: Inputs) {
+        self.frames.push(inputs);
+    }
+
+    /// 回放的总时长（秒）
+    pub fn duration(&self) -> f32 {
+        self.frames.len() as f32 / FRAME_RATE as f32
+    }
+
+    /// 使用回放的种子和设置创建一局新游戏（回放的起点）
+    pub fn new_game(&self) -> Game {
+        Game::new(self.settings, self.seed)
+    }
+
+    /// 将回放转换为文本格式
+    ///
+    /// 文件头依次记录种子和设置，之后每行记录按住的键和连续保持不变的帧数
+    pub fn to_text(&self) -> String {
+        let settings = &self.settings;
+        let mut text = String::new();
+        let mut line = |line: String| {
+            text.push_str(&line);
+            text.push('\n');
+        };
+        line(REPLAY_HEADER.to_string());
+        line(format!("seed {}", self.seed));
+        line(format!("mode {}", settings.mode.name()));
+        line(format!("start_level {}", settings.start_level));
+        line(format!("randomizer {}", settings.randomizer.name()));
+        line(format!(
+            "lock_delay_mode {}",
+            lock_delay_mode_name(settings.lock_delay_mode)
+        ));
+        line(format!(
+            "top_out {} {} {}",
+            settings.top_out.block_out as u8,
+            settings.top_out.lock_out as u8,
+            settings.top_out.partial_lock_out as u8
+        ));
+        line(format!("das_ms {}", settings.das_ms));
+        line(format!("arr_ms {}", settings.arr_ms));
+        line(format!(
+            "soft_drop {}",
+            match settings.soft_drop {
+                SoftDropSpeed::Factor(factor) => factor.to_string(),
+                SoftDropSpeed::Instant => "instant".to_string(),
+            }
+        ));
+        line(format!("entry_delay_ms {}", settings.entry_delay_ms));
+        line(format!(
+            "line_clear_delay_ms {}",
+            settings.line_clear_delay_ms
+        ));
+        line("frames".to_string());
+        for run in self.frames.chunk_by(|a, b| a == b) {
+            line(format!("{} {}", encode_inputs(&run[0]), run.len()));
+        }
+        text
+    }
+
+    /// 从文本格式解析回放
+    ///
+    /// # 返回
+    /// 解析成功返回回放，格式错误时返回出错的行号和原因
+    pub fn parse(text: &str) -> Result<Self, ReplayParseError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+        let error = |line: usize, message: &str| ReplayParseError {
+            line,
+            message: message.to_string(),
+        };
+
+        if lines.next().map(|(_, line)| line) != Some(REPLAY_HEADER) {
+            return Err(error(1, "不是回放文件"));
+        }
+
+        // 解析文件头，缺少的设置项使用默认值
+        let mut seed = None;
+        let mut settings = Settings::default();
+        loop {
+            let Some((number, line)) = lines.next() else {
+                return Err(error(0, "缺少输入记录"));
+            };
+            if line == "frames" {
+                break;
+            }
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let invalid = || error(number, &format!("无效的 {key}"));
+            match key {
+                "seed" => seed = Some(value.parse().map_err(|_| invalid())?),
+                "mode" => settings.mode = GameMode::from_name(value).ok_or_else(invalid)?,
+                "start_level" => settings.start_level = value.parse().map_err(|_| invalid())?,
+                "randomizer" => {
+                    settings.randomizer = RandomizerKind::from_name(value).ok_or_else(invalid)?
+                }
+                "lock_delay_mode" => {
+                    settings.lock_delay_mode = parse_lock_delay_mode(value).ok_or_else(invalid)?
+                }
+                "top_out" => {
+                    let flags: Vec<_> = value.split(' ').map(parse_flag).collect();
+                    let [Some(block_out), Some(lock_out), Some(partial_lock_out)] = flags[..]
+                    else {
+                        return Err(invalid());
+                    };
+                    settings.top_out = TopOutRules {
+                        block_out,
+                        lock_out,
+                        partial_lock_out,
+                    };
+                }
+                "das_ms" => settings.das_ms = parse_timing(value).ok_or_else(invalid)?,
+                "arr_ms" => settings.arr_ms = parse_timing(value).ok_or_else(invalid)?,
+                "soft_drop" => {
+                    settings.soft_drop = match value {
+                        "instant" => SoftDropSpeed::Instant,
+                        factor => SoftDropSpeed::Factor(factor.parse().map_err(|_| invalid())?),
+                    }
+                }
+                "entry_delay_ms" => {
+                    settings.entry_delay_ms = parse_timing(value).ok_or_else(invalid)?
+                }
+                "line_clear_delay_ms" => {
+                    settings.line_clear_delay_ms = parse_timing(value).ok_or_else(invalid)?
+                }
+                _ => return Err(error(number, &format!("未知的设置项 {key}"))),
+            }
+        }
+        let seed = seed.ok_or_else(|| error(0, "缺少种子"))?;
+
+        // 解析每段连续相同的输入
+        let mut frames = Vec::new();
+        for (number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let invalid = || error(number, "无效的输入记录");
+            let (flags, count) = line.split_once(' ').ok_or_else(invalid)?;
+            let inputs = decode_inputs(flags).ok_or_else(invalid)?;
+            let count: usize = count.parse().map_err(|_| invalid())?;
+            if count > MAX_REPLAY_FRAMES - frames.len() {
+                return Err(error(number, "回放过长"));
+            }
+            frames.extend(std::iter::repeat_n(inputs, count));
+        }
+
+        Ok(Self {
+            seed,
+            settings,
+            frames,
+        })
+    }
+}
+
+/// 回放解析错误
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplayParseError {
+    /// 出错的行号（从 1 开始，0 表示文件不完整）
+    pub line: usize,
+    /// 错误原因
+    pub message: String,
+}
+
+impl fmt::Display for ReplayParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "回放文件不完整：{}", self.message)
+        } else {
+            write!(f, "回放文件第 {} 行：{}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ReplayParseError {}
+
+/// 回放播放器
+/// 记录回放的播放位置，将录制的输入逐帧交给游戏模拟。
+/// 播放器不持有游戏，回放起点的游戏由 `Replay::new_game` 创建
+#[derive(Clone, Debug)]
+pub struct ReplayPlayer {
+    /// 正在播放的回放
+    pub replay: Replay,
+    /// 下一帧要播放的序号（已播放的帧数）
+    position: usize,
+}
+
+impl ReplayPlayer {
+    /// 创建从头开始播放的回放播放器
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            position: 0,
+        }
+    }
+
+    /// 已播放的帧数
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 已播放的时间（秒）
+    pub fn elapsed(&self) -> f32 {
+        self.position as f32 / FRAME_RATE as f32
+    }
+
+    /// 是否已经播放到回放末尾
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.replay.frames.len()
+    }
+
+    /// 播放下一帧
+    ///
+    /// # 返回
+    /// true 表示播放成功，false 表示已到回放末尾
+    pub fn step(&mut self, game: &mut Game) -> bool {
+        let Some(&inputs) = self.replay.frames.get(self.position) else {
+            return false;
+        };
+        game.step(inputs);
+        self.position += 1;
+        true
+    }
+
+    /// 跳转到指定的帧
+    ///
+    /// 向后跳转时直接快进；向前跳转时从回放起点重新开始模拟，再快进到目标帧
+    ///
+    /// # 参数
+    /// * `game` - 正在播放的游戏
+    /// * `position` - 目标帧序号（超过回放长度时跳到末尾）
+    pub fn seek(&mut self, game: &mut Game, position: usize) {
+        if position < self.position {
+            *game = self.replay.new_game();
+            self.position = 0;
+        }
+        while self.position < position && self.step(game) {}
+    }
+}
+
+/// 获取锁定延迟模式在回放文件中的名称
+fn lock_delay_mode_name(mode: LockDelayMode) -> &'static str {
+    match mode {
+        LockDelayMode::Infinite => "infinite",
+        LockDelayMode::MoveReset => "move_reset",
+        LockDelayMode::StepReset => "step_reset",
+    }
+}
+
+/// 根据名称解析锁定延迟模式
+fn parse_lock_delay_mode(name: &str) -> Option<LockDelayMode> {
+    match name {
+        "infinite" => Some(LockDelayMode::Infinite),
+        "move_reset" => Some(LockDelayMode::MoveReset),
+        "step_reset" => Some(LockDelayMode::StepReset),
+        _ => None,
+    }
+}
+
+/// 解析开关设置（只接受 0 和 1）
+fn parse_flag(flag: &str) -> Option<bool> {
+    match flag {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+/// 解析计时设置（毫秒），超过 MAX_REPLAY_TIMING_MS 时视为无效
+fn parse_timing(value: &str) -> Option<u32> {
+    value.parse().ok().filter(|&ms| ms <= MAX_REPLAY_TIMING_MS)
+}
+
+/// 将输入编码为标志字符串（按住的键用对应字母表示，未按住用 '.' 表示）
+fn encode_inputs(inputs: &Inputs) -> String {
+    input_flags(inputs)
+        .iter()
+        .zip(INPUT_FLAGS)
+        .map(|(&held, flag)| if held { flag } else { '.' })
+        .collect()
+}
+
+/// 将标志字符串解码为输入
+fn decode_inputs(flags: &str) -> Option<Inputs> {
+    let chars: Vec<char> = flags.chars().collect();
+    if chars.len() != INPUT_FLAGS.len() {
+        return None;
+    }
+    let mut held = [false; 8];
+    for (i, (&c, flag)) in chars.iter().zip(INPUT_FLAGS).enumerate() {
+        held[i] = match c {
+            '.' => false,
+            c if c == flag => true,
+            _ => return None,
+        };
+    }
+    let [
+        left,
+        right,
+        soft_drop,
+        hard_drop,
+        rotate_cw,
+        rotate_ccw,
+        rotate_180,
+        hold,
+    ] = held;
+    Some(Inputs {
+        left,
+        right,
+        soft_drop,
+        hard_drop,
+        rotate_cw,
+        rotate_ccw,
+        rotate_180,
+        hold,
+    })
+}
+
+/// 按 INPUT_FLAGS 的顺序列出各个键是否按住
+fn input_flags(inputs: &Inputs) -> [bool; 8] {
+    [
+        inputs.left,
+        inputs.right,
+        inputs.soft_drop,
+        inputs.hard_drop,
+        inputs.rotate_cw,
+        inputs.rotate_ccw,
+        inputs.rotate_180,
+        inputs.hold,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::random_inputs;
+
+    /// 录制一局由随机输入驱动的游戏
+    fn record(settings: Settings, seed: u64, frames: usize) -> (Replay, Game) {
+        let mut replay = Replay::new(seed, settings);
+        let mut game = Game::new(settings, seed);
+        for inputs in random_inputs(seed, frames) {
+            replay.record(inputs);
+            game.step(inputs);
+        }
+        (replay, game)
+    }
+
+    #[test]
+    fn replay_round_trips_through_text() {
+        let settings = Settings {
+            mode: GameMode::Sprint { lines: 20 },
+            start_level: 3,
+            randomizer: RandomizerKind::TgmHistory { rolls: 6 },
+            soft_drop: SoftDropSpeed::Instant,
+            lock_delay_mode: LockDelayMode::StepReset,
+            das_ms: 120,
+            ..Settings::default()
+        };
+        let (replay, _) = record(settings, 21, 2000);
+        assert_eq!(Replay::parse(&replay.to_text()), Ok(replay));
+    }
+
+    #[test]
+    fn playback_reproduces_the_recorded_game() {
+        for kind in RandomizerKind::ALL {
+            let settings = Settings {
+                randomizer: kind,
+                ..Settings::default()
+            };
+            let (replay, recorded) = record(settings, 22, 2500);
+            let replay = Replay::parse(&replay.to_text()).unwrap();
+
+            let mut player = ReplayPlayer::new(replay);
+            let mut game = player.replay.new_game();
+            while player.step(&mut game) {}
+            assert_eq!(game.board, recorded.board);
+            assert_eq!(game.score, recorded.score);
+
+            // 向前跳转后重新播放到末尾，结果不变
+            player.seek(&mut game, 600);
+            assert_eq!(player.position(), 600);
+            player.seek(&mut game, usize::MAX);
+            assert!(player.is_finished());
+            assert_eq!(game.board, recorded.board);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_malformed_files() {
+        assert_eq!(Replay::parse("hello").unwrap_err().line, 1);
+        assert_eq!(Replay::parse("tetris-replay 2\n").unwrap_err().line, 1);
+        let text = format!("{REPLAY_HEADER}\nseed 1\nframes\nQ 3\n");
+        assert_eq!(Replay::parse(&text).unwrap_err().line, 4);
+        let text = format!("{REPLAY_HEADER}\nframes\n");
+        assert_eq!(Replay::parse(&text).unwrap_err().line, 0);
+    }
+
+    #[test]
+    fn parse_rejects_out_of_range_settings() {
+        let parse = |setting: &str| {
+            let text = format!("{REPLAY_HEADER}\nseed 1\n{setting}\nframes\n");
+            Replay::parse(&text).map(|replay| replay.settings)
+        };
+        for key in ["das_ms", "arr_ms", "entry_delay_ms", "line_clear_delay_ms"] {
+            assert!(parse(&format!("{key} {MAX_REPLAY_TIMING_MS}")).is_ok());
+            let error = parse(&format!("{key} {}", MAX_REPLAY_TIMING_MS + 1)).unwrap_err();
+            assert_eq!(error.line, 3);
+        }
+
+        let top_out = parse("top_out 0 1 1").unwrap().top_out;
+        assert!(!top_out.block_out && top_out.lock_out && top_out.partial_lock_out);
+        for flags in ["1 1 2", "1 1 yes", "1 1", "1 1 1 1"] {
+            assert_eq!(parse(&format!("top_out {flags}")).unwrap_err().line, 3);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_oversized_frame_counts() {
+        let text = format!("{REPLAY_HEADER}\nseed 1\nframes\n........ {}\n", usize::MAX);
+        assert_eq!(Replay::parse(&text).unwrap_err().line, 4);
+
+        // 每段都不超过上限，但总帧数超过上限
+        let half = MAX_REPLAY_FRAMES / 2 + 1;
+        let text = format!("{REPLAY_HEADER}\nseed 1\nframes\n........ {half}\nL....... {half}\n");
+        assert_eq!(Replay::parse(&text).unwrap_err().line, 5);
+
+        let text = format!("{REPLAY_HEADER}\nseed 1\nframes\n........ {MAX_REPLAY_FRAMES}\n");
+        assert_eq!(
+            Replay::parse(&text).unwrap().frames.len(),
+            MAX_REPLAY_FRAMES
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// 游戏设置模块：操作手感和规则选项
+
+use crate::mode::GameMode;
+use crate::randomizer::RandomizerKind;
+
+/// 游戏设置
+/// 存储玩家可调整的操作手感和规则选项
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Settings {
+    /// 游戏模式（目标和结束条件）
+    pub mode: GameMode,
+    /// 起始等级（1 到 MAX_START_LEVEL）
+    pub start_level: u32,
+    /// 锁定延迟的重置规则
+    pub lock_delay_mode: LockDelayMode,
+    /// 启用的溢出（游戏结束）规则
+    pub top_out: TopOutRules,
+    /// 延迟自动移位（DAS）：按住左右键后开始自动重复前的等待时间（毫秒）
+    pub das_ms: u32,
+    /// 自动重复速率（ARR）：自动重复时每移动一格的间隔（毫秒，0 表示瞬间移到墙边）
+    pub arr_ms: u32,
+    /// 按住下键时的软降速度
+    pub soft_drop: SoftDropSpeed,
+    /// 出块等待（ARE）：方块锁定后到下一个方块出现的时间（毫秒）
+    pub entry_delay_ms: u32,
+    /// 消行等待：满行消除前保持显示的时间（毫秒）
+    pub line_clear_delay_ms: u32,
+    /// 决定方块出现顺序的随机器类型
+    pub randomizer: RandomizerKind,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            start_level: 1,
+            lock_delay_mode: LockDelayMode::MoveReset,
+            top_out: TopOutRules::default(),
+            das_ms: 167,
+            arr_ms: 33,
+            soft_drop: SoftDropSpeed::Factor(20),
+            entry_delay_ms: 100,
+            line_clear_delay_ms: 250,
+            randomizer: RandomizerKind::SevenBag,
+        }
+    }
+}
+
+/// 锁定延迟模式
+/// 决定方块触底后哪些操作可以重置锁定计时器
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LockDelayMode {
+    /// 无限重置：每次成功平移或旋转都会重置计时器，可无限拖延
+    Infinite,
+    /// 移动重置：平移或旋转可重置计时器，但每个方块最多 MAX_LOCK_RESETS 次，
+    /// 到达新的最低行时次数清零
+    MoveReset,
+    /// 步进重置：只有到达新的最低行才会重置计时器
+    StepReset,
+}
+
+/// 溢出规则
+/// 分别控制每种溢出条件是否导致游戏结束
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TopOutRules {
+    /// 生成位置被占用时游戏结束（关闭时尝试在缓冲区内上移生成）
+    pub block_out: bool,
+    /// 方块完全锁定在可见区域上方时游戏结束
+    pub lock_out: bool,
+    /// 方块有任意格子锁定在可见区域上方时游戏结束
+    pub partial_lock_out: bool,
+}
+
+impl Default for TopOutRules {
+    fn default() -> Self {
+        Self {
+            block_out: true,
+            lock_out: true,
+            partial_lock_out: false,
+        }
+    }
+}
+
+/// 软降速度
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SoftDropSpeed {
+    /// 按当前重力速度的倍数下落（软降因子）
+    Factor(u32),
+    /// 瞬间下落到落点，但不锁定
+    Instant,
+}
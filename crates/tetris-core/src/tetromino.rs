@@ -0,0 +1,475 @@
+// 俄罗斯方块类型和逻辑模块
+
+use crate::board::Board;
+use crate::random::Rng;
+use std::sync::OnceLock;
+
+/// 俄罗斯方块类型枚举
+///
+/// 包含7种标准俄罗斯方块类型：
+/// - I: 直线（青色，4格连成一线）
+/// - O: 正方形（黄色，2x2方形）
+/// - T: T形（紫色）
+/// - S: S形（绿色）
+/// - Z: Z形（红色）
+/// - J: J形（蓝色）
+/// - L: L形（橙色）
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TetrominoType {
+    I,
+    O,
+    T,
+    S,
+    Z,
+    J,
+    L,
+}
+
+impl TetrominoType {
+    /// 全部 7 种方块类型
+    pub const ALL: [TetrominoType; 7] = [
+        TetrominoType::I,
+        TetrominoType::O,
+        TetrominoType::T,
+        TetrominoType::S,
+        TetrominoType::Z,
+        TetrominoType::J,
+        TetrominoType::L,
+    ];
+
+    /// 使用指定的随机数生成器随机生成一个俄罗斯方块类型
+    pub fn random(rng: &mut Rng) -> Self {
+        Self::ALL[rng.below(Self::ALL.len())]
+    }
+
+    /// 获取方块的形状（相对坐标）
+    /// 返回方块在出生朝向下，相对于包围盒左上角的四个格子位置
+    pub fn shape(&self) -> Vec<(i32, i32)> {
+        match self {
+            // I 形：四格成一条线（位于 4x4 包围盒的第二行）
+            TetrominoType::I => vec![(0, 1), (1, 1), (2, 1), (3, 1)],
+            // O 形：2x2 正方形
+            TetrominoType::O => vec![(0, 0), (1, 0), (0, 1), (1, 1)],
+            // T 形：T 字形
+            TetrominoType::T => vec![(1, 0), (0, 1), (1, 1), (2, 1)],
+            // S 形：Z 字向左倾
+            TetrominoType::S => vec![(1, 0), (2, 0), (0, 1), (1, 1)],
+            // Z 形：Z 字向右倾
+            TetrominoType::Z => vec![(0, 0), (1, 0), (1, 1), (2, 1)],
+            // J 形：J 字形
+            TetrominoType::J => vec![(0, 0), (0, 1), (1, 1), (2, 1)],
+            // L 形：L 字形
+            TetrominoType::L => vec![(2, 0), (0, 1), (1, 1), (2, 1)],
+        }
+    }
+
+    /// 获取方块包围盒的边长
+    ///
+    /// SRS 中 I 使用 4x4 包围盒，O 使用 2x2 包围盒，其余方块使用 3x3 包围盒，
+    /// 方块始终绕包围盒的几何中心旋转
+    pub fn box_size(&self) -> i32 {
+        match self {
+            TetrominoType::I => 4,
+            TetrominoType::O => 2,
+            _ => 3,
+        }
+    }
+
+    /// 获取方块在指定旋转状态下的格子位置（相对包围盒左上角）
+    ///
+    /// # 参数
+    /// * `rotation` - 旋转状态（0-3，表示 0、R、2、L）
+    pub fn cells(&self, rotation: u8) -> Vec<(i32, i32)> {
+        // 以两倍坐标表示，使 4x4 包围盒的中心 (1.5, 1.5) 也能用整数表示
+        let span = self.box_size() - 1;
+        self.shape()
+            .into_iter()
+            .map(|(x, y)| {
+                let (mut cx, mut cy) = (2 * x - span, 2 * y - span);
+                // 屏幕坐标系 Y 轴向下，(x, y) -> (-y, x) 即顺时针旋转 90°
+                for _ in 0..rotation % 4 {
+                    (cx, cy) = (-cy, cx);
+                }
+                ((cx + span) / 2, (cy + span) / 2)
+            })
+            .collect()
+    }
+
+    /// 获取方块在指定旋转状态下包围盒内每行的占用位图（第 dx 位对应包围盒的第 dx 列）
+    ///
+    /// 位图在首次使用时为所有方块和旋转状态计算一次，供游戏板的位运算碰撞检测使用
+    ///
+    /// # 参数
+    /// * `rotation` - 旋转状态（0-3，表示 0、R、2、L）
+    pub fn row_masks(&self, rotation: u8) -> &'static [u16; 4] {
+        static MASKS: OnceLock<[[[u16; 4]; 4]; 7]> = OnceLock::new();
+        let masks = MASKS.get_or_init(|| {
+            TetrominoType::ALL.map(|tetromino_type| {
+                [0, 1, 2, 3].map(|rotation| {
+                    let mut rows = [0; 4];
+                    for (dx, dy) in tetromino_type.cells(rotation) {
+                        rows[dy as usize] |= 1 << dx;
+                    }
+                    rows
+                })
+            })
+        });
+        &masks[*self as usize][rotation as usize % 4]
+    }
+
+    /// 获取从 `from` 旋转到 `to` 时依次尝试的踢墙偏移（网格坐标，Y 轴向下）
+    ///
+    /// 踢墙表来自 SRS 标准，按顺序尝试，第一个不碰撞的偏移即为最终位置
+    pub fn kicks(&self, from: u8, to: u8) -> Vec<(i32, i32)> {
+        let (from, to) = (from % 4, to % 4);
+        let table = match self {
+            TetrominoType::O => return vec![(0, 0)],
+            TetrominoType::I => &SRS_KICKS_I,
+            _ => &SRS_KICKS_JLSTZ,
+        };
+
+        // 表中只记录顺时针方向，逆时针踢墙为对应顺时针踢墙取反
+        let offsets: Vec<(i32, i32)> = if to == (from + 2) % 4 {
+            KICKS_180[from as usize].to_vec()
+        } else if to == (from + 1) % 4 {
+            table[from as usize].to_vec()
+        } else {
+            table[to as usize].iter().map(|&(x, y)| (-x, -y)).collect()
+        };
+
+        // 标准表使用 Y 轴向上的坐标，转换为网格坐标（Y 轴向下）
+        offsets.into_iter().map(|(x, y)| (x, -y)).collect()
+    }
+}
+
+/// J、L、S、T、Z 方块的 SRS 顺时针踢墙表（Y 轴向上）
+/// 下标为起始旋转状态：0->R、R->2、2->L、L->0
+const SRS_KICKS_JLSTZ: [[(i32, i32); 5]; 4] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+/// I 方块的 SRS 顺时针踢墙表（Y 轴向上）
+/// 下标为起始旋转状态：0->R、R->2、2->L、L->0
+const SRS_KICKS_I: [[(i32, i32); 5]; 4] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+];
+
+/// 180° 旋转踢墙表（Y 轴向上，所有非 O 方块通用）
+/// 下标为起始旋转状态：0->2、R->L、2->0、L->R
+const KICKS_180: [[(i32, i32); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+/// 旋转方向
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Rotation {
+    /// 顺时针旋转 90°
+    Clockwise,
+    /// 逆时针旋转 90°
+    CounterClockwise,
+    /// 旋转 180°
+    Half,
+}
+
+impl Rotation {
+    /// 获取该方向对应的旋转状态增量（0-3 循环）
+    pub fn steps(&self) -> u8 {
+        match self {
+            Rotation::Clockwise => 1,
+            Rotation::Half => 2,
+            Rotation::CounterClockwise => 3,
+        }
+    }
+}
+
+/// T-spin 判定结果
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TSpin {
+    /// 不是 T-spin
+    None,
+    /// 迷你 T-spin（T 尖端前方的两个角未被同时占据）
+    Mini,
+    /// 完整 T-spin
+    Full,
+}
+
+/// 活跃方块（当前下落的方块）
+/// 存储方块的类型、位置和旋转状态
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ActivePiece {
+    /// 方块类型
+    pub tetromino_type: TetrominoType,
+    /// 方块包围盒左上角的 X 坐标（网格坐标）
+    pub x: i32,
+    /// 方块包围盒左上角的 Y 坐标（网格坐标）
+    pub y: i32,
+    /// 旋转状态（0-3，表示 0、R、2、L，即 0°、90°、180°、270°）
+    pub rotation: u8,
+    /// 最后一次成功操作为旋转时的旋转方向和所用踢墙在踢墙表中的下标
+    /// （平移或下落后清空，用于 T-spin 判定）
+    pub last_kick: Option<(Rotation, usize)>,
+}
+
+impl ActivePiece {
+    /// 创建新的活跃方块
+    /// 方块在可见区域正上方居中生成，占据缓冲区最下方的两行（即标准规则中的第 21-22 行）
+    pub fn new(tetromino_type: TetrominoType) -> Self {
+        let size = tetromino_type.box_size();
+        let top = tetromino_type
+            .shape()
+            .iter()
+            .map(|&(_, y)| y)
+            .min()
+            .unwrap_or(0);
+        Self {
+            tetromino_type,
+            x: (crate::constants::GRID_WIDTH as i32 - size) / 2,
+            y: crate::constants::BUFFER_HEIGHT as i32 - 2 - top,
+            rotation: 0,
+            last_kick: None,
+        }
+    }
+
+    /// 获取方块当前的所有格子位置（考虑旋转）
+    /// 返回包含方块占据的所有格子的绝对坐标
+    pub fn blocks(&self) -> Vec<(i32, i32)> {
+        self.tetromino_type
+            .cells(self.rotation)
+            .into_iter()
+            .map(|(dx, dy)| (self.x + dx, self.y + dy))
+            .collect()
+    }
+
+    /// 检查方块移动后是否会发生碰撞
+    ///
+    /// # 参数
+    /// * `dx` - X 方向的移动偏移量
+    /// * `dy` - Y 方向的移动偏移量
+    /// * `board` - 游戏板引用
+    ///
+    /// # 返回
+    /// true 表示会碰撞（方块不能移动到目标位置），false 表示可以移动
+    pub fn check_collision(&self, dx: i32, dy: i32, board: &Board) -> bool {
+        board.collides(
+            self.tetromino_type.row_masks(self.rotation),
+            self.x + dx,
+            self.y + dy,
+        )
+    }
+
+    /// 获取方块硬降后的落点（不断下移直到碰撞）
+    pub fn drop_position(&self, board: &Board) -> ActivePiece {
+        let mut landed = *self;
+        while !landed.check_collision(0, 1, board) {
+            landed.y += 1;
+        }
+        landed
+    }
+
+    /// 尝试按 SRS 规则向指定方向旋转方块
+    ///
+    /// 依次尝试踢墙表中的每个偏移，返回第一个不发生碰撞的位置；
+    /// 所有偏移都碰撞时返回 None（旋转失败）
+    pub fn try_rotate(&self, direction: Rotation, board: &Board) -> Option<ActivePiece> {
+        let to = (self.rotation + direction.steps()) % 4;
+        self.tetromino_type
+            .kicks(self.rotation, to)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (dx, dy))| ActivePiece {
+                x: self.x + dx,
+                y: self.y + dy,
+                rotation: to,
+                last_kick: Some((direction, index)),
+                ..*self
+            })
+            .find(|candidate| !candidate.check_collision(0, 0, board))
+    }
+
+    /// 判定方块在当前位置锁定时是否构成 T-spin
+    ///
+    /// 采用三角规则：T 方块最后一次操作为旋转，且旋转中心四个对角中至少三个被占据
+    /// （墙壁和地面也算占据）。T 尖端一侧的两个角都被占据时为完整 T-spin，
+    /// 否则为迷你 T-spin；但 90° 旋转使用 SRS 第 5 个踢墙（1x2 的 TST 踢墙）到达时
+    /// 总是算作完整 T-spin
+    pub fn t_spin(&self, board: &Board) -> TSpin {
+        use crate::constants::*;
+
+        if self.tetromino_type != TetrominoType::T {
+            return TSpin::None;
+        }
+        let Some((direction, kick)) = self.last_kick else {
+            return TSpin::None;
+        };
+
+        // T 方块的旋转中心为 3x3 包围盒的中心
+        let (cx, cy) = (self.x + 1, self.y + 1);
+        let occupied = |dx: i32, dy: i32| {
+            let (x, y) = (cx + dx, cy + dy);
+            x < 0
+                || x >= GRID_WIDTH as i32
+                || y < 0
+                || y >= TOTAL_HEIGHT as i32
+                || !board.is_empty(x as usize, y as usize)
+        };
+
+        // 四个对角按顺时针排列：左上、右上、右下、左下，
+        // 旋转状态 r 时 T 尖端一侧的两个角为第 r 和 r+1 个
+        let corners = [(-1, -1), (1, -1), (1, 1), (-1, 1)].map(|(dx, dy)| occupied(dx, dy));
+        if corners.iter().filter(|&&filled| filled).count() < 3 {
+            return TSpin::None;
+        }
+
+        let front = self.rotation as usize % 4;
+        let front_filled = corners[front] && corners[(front + 1) % 4];
+        let tst_kick = direction != Rotation::Half && kick == 4;
+        if front_filled || tst_kick {
+            TSpin::Full
+        } else {
+            TSpin::Mini
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TOTAL_HEIGHT;
+
+    fn piece(tetromino_type: TetrominoType, x: i32, y: i32, rotation: u8) -> ActivePiece {
+        ActivePiece {
+            tetromino_type,
+            x,
+            y,
+            rotation,
+            last_kick: None,
+        }
+    }
+
+    #[test]
+    fn t_kicks_up_off_the_floor() {
+        let board = Board::default();
+        // 平放在地面上的 T 顺时针旋转时会伸出地面，使用第 3 个踢墙左移并上移一格
+        let y = TOTAL_HEIGHT as i32 - 2;
+        let rotated = piece(TetrominoType::T, 4, y, 0)
+            .try_rotate(Rotation::Clockwise, &board)
+            .unwrap();
+        assert_eq!((rotated.x, rotated.y, rotated.rotation), (3, y - 1, 1));
+        assert_eq!(rotated.last_kick, Some((Rotation::Clockwise, 2)));
+    }
+
+    #[test]
+    fn i_kicks_off_the_left_wall() {
+        let board = Board::default();
+        // 竖直的 I 贴着左墙，顺时针转为横向时使用 I 专用踢墙表右移两格
+        let rotated = piece(TetrominoType::I, -2, 20, 1)
+            .try_rotate(Rotation::Clockwise, &board)
+            .unwrap();
+        assert_eq!((rotated.x, rotated.y, rotated.rotation), (0, 20, 2));
+        assert_eq!(rotated.last_kick, Some((Rotation::Clockwise, 2)));
+    }
+
+    #[test]
+    fn rotation_fails_when_every_kick_collides() {
+        let mut board = Board::default();
+        for y in 0..TOTAL_HEIGHT {
+            for x in 0..crate::constants::GRID_WIDTH {
+                board.set_cell(x, y, Some(TetrominoType::O));
+            }
+        }
+        for x in 3..6 {
+            board.set_cell(x, 21, None);
+        }
+        board.set_cell(4, 20, None);
+        assert!(
+            piece(TetrominoType::T, 3, 20, 0)
+                .try_rotate(Rotation::Clockwise, &board)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn t_kicks_off_the_left_wall() {
+        let board = Board::default();
+        // 朝右的 T 贴着左墙，逆时针转回朝上时需要右移一格
+        let rotated = piece(TetrominoType::T, -1, 20, 1)
+            .try_rotate(Rotation::CounterClockwise, &board)
+            .unwrap();
+        assert_eq!((rotated.x, rotated.y, rotated.rotation), (0, 20, 0));
+        assert_eq!(rotated.last_kick, Some((Rotation::CounterClockwise, 1)));
+    }
+
+    #[test]
+    fn counter_clockwise_kicks_mirror_clockwise_table() {
+        for tetromino_type in [TetrominoType::T, TetrominoType::I] {
+            for from in 0..4 {
+                let to = (from + 1) % 4;
+                let back: Vec<_> = tetromino_type
+                    .kicks(to, from)
+                    .into_iter()
+                    .map(|(x, y)| (-x, -y))
+                    .collect();
+                assert_eq!(tetromino_type.kicks(from, to), back);
+            }
+        }
+    }
+
+    #[test]
+    fn half_turn_kicks_up_off_the_floor() {
+        let board = Board::default();
+        // 平放在地面上的 T 旋转 180° 后尖端朝下会伸出地面，使用第 2 个踢墙上移一格
+        let y = TOTAL_HEIGHT as i32 - 2;
+        let rotated = piece(TetrominoType::T, 4, y, 0)
+            .try_rotate(Rotation::Half, &board)
+            .unwrap();
+        assert_eq!((rotated.x, rotated.y, rotated.rotation), (4, y - 1, 2));
+        assert_eq!(rotated.last_kick, Some((Rotation::Half, 1)));
+    }
+
+    /// 朝上的 T 位于 (3, 30)：左上角和下方两个角被占据，尖端一侧只占据一个角
+    fn mini_t_spin_board() -> Board {
+        let mut board = Board::default();
+        for (x, y) in [(3, 30), (3, 32), (5, 32)] {
+            board.set_cell(x, y, Some(TetrominoType::O));
+        }
+        board
+    }
+
+    #[test]
+    fn fifth_kick_of_a_quarter_turn_is_a_full_t_spin() {
+        let board = mini_t_spin_board();
+        let mut t = piece(TetrominoType::T, 3, 30, 0);
+        t.last_kick = Some((Rotation::Clockwise, 0));
+        assert_eq!(t.t_spin(&board), TSpin::Mini);
+        t.last_kick = Some((Rotation::Clockwise, 4));
+        assert_eq!(t.t_spin(&board), TSpin::Full);
+    }
+
+    #[test]
+    fn half_turn_kicks_never_upgrade_a_mini() {
+        let board = mini_t_spin_board();
+        let mut t = piece(TetrominoType::T, 3, 30, 0);
+        for kick in 0..6 {
+            t.last_kick = Some((Rotation::Half, kick));
+            assert_eq!(t.t_spin(&board), TSpin::Mini);
+        }
+    }
+
+    #[test]
+    fn no_t_spin_without_a_rotation() {
+        let board = mini_t_spin_board();
+        assert_eq!(
+            piece(TetrominoType::T, 3, 30, 0).t_spin(&board),
+            TSpin::None
+        );
+    }
+}
@@ -8,13 +8,44 @@ use bevy::prelude::*;
 pub struct Block;
 
 /// UI 文本类型枚举组件
-/// 用于区分不同的 UI 文本元素（分数、游戏结束、暂停提示）
+/// 用于区分不同的 UI 文本元素（分数、等级、行数、模式、用时、种子、随机器、奖励提示、分段用时、
+/// 游戏结束、结算画面、暂停提示、回放状态）
 #[derive(Component, Clone, Copy, PartialEq)]
 pub enum UiText {
     /// 分数显示文本
     Score,
+    /// 等级显示文本
+    Level,
+    /// 消行数显示文本
+    Lines,
+    /// 游戏模式显示文本
+    Mode,
+    /// 本局用时显示文本
+    Timer,
+    /// 随机种子显示文本
+    Seed,
+    /// 随机器类型显示文本
+    Randomizer,
+    /// 奖励提示文本（背靠背、连击、全消等）
+    Callout,
+    /// 分段用时文本（冲线模式每消除 10 行记录一次）
+    Splits,
     /// 游戏结束提示文本
     GameOver,
+    /// 结算画面文本（达成模式目标时显示成绩）
+    Results,
     /// 暂停提示文本
     Pause,
+    /// 回放状态文本（播放进度和操作说明，或游戏结束后的保存结果）
+    Replay,
 }
+
+/// 菜单说明文本组件
+/// 标记菜单页面标题下方的说明文本（如最高分表格），内容随页面状态更新
+#[derive(Component)]
+pub struct MenuNote;
+
+/// 菜单条目组件
+/// 标记菜单页面中第几个可选条目的文本（条目较多时按光标位置滚动显示）
+#[derive(Component, Clone, Copy, PartialEq)]
+pub struct MenuItem(pub usize);
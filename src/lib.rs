@@ -1,11 +1,11 @@
 mod components;
 mod constants;
 mod resources;
+mod states;
 mod systems;
-mod tetromino;
 
 pub use components::*;
 pub use constants::*;
 pub use resources::*;
+pub use states::*;
 pub use systems::*;
-pub use tetromino::*;
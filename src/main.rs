@@ -1,44 +1,163 @@
 use bevy::prelude::*;
 use tetris::*;
+use tetris_core::constants::FRAME_RATE;
+use tetris_core::{RandomizerKind, Settings};
 
 /// 游戏入口函数
-/// 初始化 Bevy 应用程序，配置窗口、资源和系统
+/// 初始化 Bevy 应用程序，配置窗口、资源、状态和系统
+///
+/// 命令行参数：
+/// - `--seed <种子>`：指定固定的随机种子，使每局的方块序列相同
+/// - `--randomizer <类型>`：选择所有模式的随机器（7bag、14bag、random、nes、tgm4、tgm6、bag+1）
+/// - `--replay <文件>`：直接播放回放文件（不经过主菜单）
 fn main() {
-    App::new()
-        // 添加默认插件，并配置窗口参数
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Tetris".to_string(),
-                resolution: (800, 700).into(),
-                ..default()
-            }),
+    let seed_config = SeedConfig {
+        fixed: arg_value("seed").map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| usage_error("无效的种子参数，用法：--seed <非负整数>"))
+        }),
+    };
+    let mut settings = Settings::default();
+    if let Some(name) = arg_value("randomizer") {
+        settings.randomizer = RandomizerKind::from_name(&name).unwrap_or_else(|| {
+            let names: Vec<_> = RandomizerKind::ALL.iter().map(|kind| kind.name()).collect();
+            usage_error(&format!("未知的随机器类型，可选：{}", names.join("、")))
+        });
+    }
+    let mut game_state = GameState::new(settings, seed_config.next_seed());
+    let game_settings = GameSettings {
+        rules: settings,
+        randomizers: ModeRandomizers::all(settings.randomizer),
+        ..default()
+    };
+
+    // 播放回放时使用回放记录的种子和设置
+    let playback = arg_value("replay").map(|path| {
+        let replay = load_replay(&path).unwrap_or_else(|error| usage_error(&error));
+        game_state = GameState::new(replay.settings, replay.seed);
+        ReplayPlayback::new(replay)
+    });
+
+    let mut app = App::new();
+    // 添加默认插件，并配置窗口参数
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            title: "Tetris".to_string(),
+            resolution: (800, 700).into(),
             ..default()
-        }))
+        }),
+        ..default()
+    }));
+    // 初始化应用状态：播放回放时直接进入回放状态，否则从主菜单开始
+    if let Some(playback) = playback {
+        app.insert_resource(playback) // 回放播放状态
+            .insert_state(AppState::Replay);
+    } else {
+        app.init_state::<AppState>();
+    }
+    app.add_computed_state::<InGame>()
+        .add_sub_state::<MenuPage>()
         // 初始化游戏资源
-        .init_resource::<GameBoard>() // 游戏板资源
-        .init_resource::<GameState>() // 游戏状态资源
-        .init_resource::<FallTimer>() // 方块掉落计时器
+        .insert_resource(game_state) // 游戏状态资源（游戏模拟核心）
+        .insert_resource(seed_config) // 随机种子设置
+        .insert_resource(game_settings) // 玩家设置
+        .insert_resource(load_high_scores()) // 最高分记录
+        .init_resource::<MenuCursor>() // 菜单光标
+        .init_resource::<HighScoreView>() // 最高分页面显示的模式
+        .init_resource::<ReplayList>() // 回放列表
+        .init_resource::<PlayerInputs>() // 下一个固定帧的玩家输入
+        // 游戏模拟以固定帧率运行，与画面刷新率无关
+        .insert_resource(Time::<Fixed>::from_hz(FRAME_RATE as f64))
         // 添加启动系统（只执行一次）
-        .add_systems(Startup, setup_game)
+        .add_systems(Startup, setup_camera)
+        // 添加状态切换时的创建和清理系统
+        .add_systems(OnEnter(MenuPage::Main), setup_main_page) // 创建主菜单首页
+        .add_systems(OnEnter(MenuPage::Settings), setup_settings_page) // 创建设置页面
+        .add_systems(OnEnter(MenuPage::HighScores), setup_high_scores_page) // 创建最高分页面
+        .add_systems(OnEnter(MenuPage::Replays), setup_replays_page) // 创建回放列表页面
+        .add_systems(OnEnter(InGame), setup_hud) // 创建游戏信息文本
+        .add_systems(OnExit(AppState::Playing), reset_player_inputs) // 清空玩家输入
+        .add_systems(
+            OnEnter(AppState::GameOver),
+            (save_recording, record_high_score), // 保存本局回放和成绩
+        )
+        .add_systems(OnExit(AppState::Replay), end_replay) // 移除回放播放状态
+        // 添加输入系统（每次画面刷新时、在固定帧之前执行）
+        .add_systems(
+            RunFixedMainLoop,
+            (
+                handle_player_input // 处理玩家输入（键盘）
+                    .run_if(in_state(AppState::Playing)),
+                handle_replay_input // 处理回放操作（暂停、跳转、变速）
+                    .run_if(in_state(AppState::Replay)),
+            )
+                .in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop),
+        )
+        // 添加模拟系统（每个固定帧执行一次）
+        .add_systems(
+            FixedUpdate,
+            (
+                update_game_logic // 推进游戏模拟（下落、碰撞、消行等）
+                    .run_if(in_state(AppState::Playing)),
+                update_replay // 按录制的输入推进游戏模拟
+                    .run_if(in_state(AppState::Replay)),
+            ),
+        )
         // 添加更新系统
         .add_systems(
             Update,
             (
-                handle_player_input, // 处理玩家输入（键盘）
-                update_game_logic,   // 更新游戏逻辑（下落、碰撞、消行等）
-                render_game,         // 渲染游戏（绘制方块、方块预览）
-                update_ui,           // 更新 UI（分数、游戏状态提示）
+                (handle_menu_input, update_menu_items)
+                    .chain()
+                    .run_if(in_state(AppState::MainMenu)), // 主菜单操作和显示
+                handle_pause_input.run_if(in_state(AppState::Paused)), // 暂停操作
+                handle_game_over_input.run_if(in_state(AppState::GameOver)), // 游戏结束操作
+                (
+                    render_game, // 渲染游戏（绘制方块、方块预览）
+                    update_ui,   // 更新 UI（分数、游戏状态提示）
+                )
+                    .run_if(in_state(InGame)),
             ),
         )
         .run();
 }
 
-/// 初始化游戏场景
-/// 创建摄像头和 UI 文本元素（分数、游戏结束、暂停提示）
-fn setup_game(mut commands: Commands) {
-    // 创建 2D 摄像头
+/// 读取命令行参数的值
+///
+/// 支持 `--<名称> <值>` 和 `--<名称>=<值>` 两种写法，参数缺少值时返回空字符串
+fn arg_value(name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return Some(args.next().unwrap_or_default());
+        }
+        if let Some(value) = arg
+            .strip_prefix(&flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// 打印命令行参数错误提示并退出
+fn usage_error(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(2);
+}
+
+/// 创建 2D 摄像头
+fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
+}
 
+/// 创建游戏画面的 UI 文本元素（分数、等级、行数、模式、用时、奖励提示、分段用时、游戏结束、结算画面、
+/// 暂停提示、回放状态）
+/// 进入游戏画面时创建，回到主菜单时自动销毁
+fn setup_hud(mut commands: Commands) {
     // 创建分数显示文本（左上角）
     commands.spawn((
         Text::new("Score: 0"),
@@ -54,6 +173,92 @@ fn setup_game(mut commands: Commands) {
             ..default()
         },
         UiText::Score,
+        DespawnOnExit(InGame),
+    ));
+
+    // 创建等级、消行数、模式、用时、种子和随机器显示文本（分数下方）
+    for (i, ui_type) in [
+        UiText::Level,
+        UiText::Lines,
+        UiText::Mode,
+        UiText::Timer,
+        UiText::Seed,
+        UiText::Randomizer,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Text::new(""),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(50.0 + i as f32 * 30.0),
+                left: Val::Px(20.0),
+                ..default()
+            },
+            ui_type,
+            DespawnOnExit(InGame),
+        ));
+    }
+
+    // 创建奖励提示文本（左侧，等级和行数下方）
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 22.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.8, 0.2)), // 金色
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(400.0),
+            left: Val::Px(20.0),
+            ..default()
+        },
+        UiText::Callout,
+        DespawnOnExit(InGame),
+    ));
+
+    // 创建分段用时文本（右下角）
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(60.0),
+            right: Val::Px(20.0),
+            ..default()
+        },
+        UiText::Splits,
+        DespawnOnExit(InGame),
+    ));
+
+    // 创建结算画面文本（中心）
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.4, 1.0, 0.4)), // 绿色
+        TextLayout::new_with_justify(Justify::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            left: Val::Px(250.0),
+            ..default()
+        },
+        UiText::Results,
+        DespawnOnExit(InGame),
     ));
 
     // 创建游戏结束提示文本（中心）
@@ -71,6 +276,7 @@ fn setup_game(mut commands: Commands) {
             ..default()
         },
         UiText::GameOver,
+        DespawnOnExit(InGame),
     ));
 
     // 创建暂停提示文本（中心上方）
@@ -88,5 +294,24 @@ fn setup_game(mut commands: Commands) {
             ..default()
         },
         UiText::Pause,
+        DespawnOnExit(InGame),
+    ));
+
+    // 创建回放状态文本（底部）
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)), // 灰色
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(20.0),
+            ..default()
+        },
+        UiText::Replay,
+        DespawnOnExit(InGame),
     ));
 }
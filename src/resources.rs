@@ -1,166 +1,286 @@
 // 游戏资源模块：定义游戏全局状态和数据
 
-use crate::constants::*;
-use crate::tetromino::{ActivePiece, TetrominoType};
 use bevy::prelude::*;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tetris_core::{Game, GameMode, Inputs, RandomizerKind, Replay, ReplayPlayer, Settings};
 
-/// 游戏板资源
-/// 存储游戏板上已锁定方块的信息，使用二维数组表示网格
+/// 游戏状态资源
+/// 持有无引擎依赖的游戏模拟核心和本局的回放录制（暂停、游戏结束等流程由 `AppState` 控制）
 #[derive(Resource)]
-pub struct GameBoard {
-    /// 游戏板单元格数组，行x列的二维数组，每个单元格存储颜色（None表示空）
-    pub cells: [[Option<Color>; GRID_WIDTH]; GRID_HEIGHT],
+pub struct GameState {
+    /// 游戏模拟（游戏板、方块、得分和规则）
+    pub game: Game,
+    /// 本局的回放录制（种子、设置和每帧输入）
+    pub recording: Replay,
+    /// 游戏结束后回放的保存结果（保存路径或错误信息）
+    pub replay_status: Option<String>,
 }
 
-impl Default for GameBoard {
-    fn default() -> Self {
+impl GameState {
+    /// 使用指定设置和随机种子创建一局新游戏
+    pub fn new(settings: Settings, seed: u64) -> Self {
         Self {
-            cells: [[None; GRID_WIDTH]; GRID_HEIGHT],
+            game: Game::new(settings, seed),
+            recording: Replay::new(seed, settings),
+            replay_status: None,
         }
     }
-}
 
-impl GameBoard {
-    /// 检查指定单元格是否为空
-    pub fn is_empty(&self, x: usize, y: usize) -> bool {
-        self.cells[y][x].is_none()
+    /// 使用新的随机种子重新开始游戏（保留当前设置）
+    pub fn restart(&mut self, seed: u64) {
+        *self = Self::new(self.game.settings, seed);
     }
+}
 
-    /// 设置指定单元格的颜色
-    ///
-    /// # 参数
-    /// * `x` - 列坐标
-    /// * `y` - 行坐标
-    /// * `color` - 要设置的颜色（None表示清空）
-    pub fn set_cell(&mut self, x: usize, y: usize, color: Option<Color>) {
-        self.cells[y][x] = color;
-    }
+/// 回放播放资源
+/// 播放回放（`AppState::Replay`）时游戏由回放输入驱动，退出回放时移除
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    /// 回放播放器
+    pub player: ReplayPlayer,
+    /// 播放是否暂停
+    pub paused: bool,
+    /// 当前播放速度在 SPEEDS 中的下标
+    pub speed_index: usize,
+    /// 按播放速度累计、尚未播放的帧数
+    pub pending: f32,
+}
 
-    /// 清空指定行（消行时使用）
-    pub fn clear_line(&mut self, line: usize) {
-        self.cells[line] = [None; GRID_WIDTH];
-    }
+impl ReplayPlayback {
+    /// 可选的播放速度倍率
+    pub const SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
 
-    /// 将指定行及以上的所有行向下移动一行（消行后移动）
-    ///
-    /// # 参数
-    /// * `from_line` - 要开始移动的行号
-    pub fn shift_lines_down(&mut self, from_line: usize) {
-        // 从上往下遍历，将每一行复制到下一行
-        for y in (1..=from_line).rev() {
-            self.cells[y] = self.cells[y - 1];
+    /// 创建从头开始、以原速播放的回放
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            player: ReplayPlayer::new(replay),
+            paused: false,
+            speed_index: 2,
+            pending: 0.0,
         }
-        // 顶部行清空
-        self.cells[0] = [None; GRID_WIDTH];
     }
 
-    /// 检查指定行是否已满（所有单元格都有方块）
-    pub fn is_line_full(&self, line: usize) -> bool {
-        self.cells[line].iter().all(|cell| cell.is_some())
+    /// 当前播放速度倍率
+    pub fn speed(&self) -> f32 {
+        Self::SPEEDS[self.speed_index]
     }
 }
 
-/// 游戏状态资源
-/// 管理当前游戏的运行状态和得分
+/// 随机种子资源
+/// 决定每局游戏使用的随机种子
+#[derive(Resource, Default)]
+pub struct SeedConfig {
+    /// 命令行指定的固定种子（None 表示每局使用新的随机种子）
+    pub fixed: Option<u64>,
+}
+
+impl SeedConfig {
+    /// 获取下一局游戏使用的种子
+    /// 指定了固定种子时每局都使用该种子，否则根据当前系统时间生成
+    pub fn next_seed(&self) -> u64 {
+        self.fixed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos() as u64)
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// 游戏设置资源
+/// 存储玩家可调整的显示选项，以及开始新游戏时使用的操作手感和规则选项
 #[derive(Resource)]
-pub struct GameState {
-    /// 当前下落的方块（None表示没有活跃方块）
-    pub current_piece: Option<ActivePiece>,
-    /// 下一个将要出现的方块类型
-    pub next_piece: TetrominoType,
-    /// 当前分数
-    pub score: u32,
-    /// 游戏是否结束
-    pub game_over: bool,
-    /// 游戏是否暂停
-    pub paused: bool,
-    /// 7-Bag 随机系统的袋子（存储待发放的方块类型）
-    bag: Vec<TetrominoType>,
-    /// 锁定延迟计时器（None表示未触底，Some(时间)表示已触底并计时）
-    pub lock_timer: Option<f32>,
+pub struct GameSettings {
+    /// 预览区显示的后续方块数量（1 到 MAX_PREVIEW_COUNT）
+    pub preview_count: usize,
+    /// 是否显示幽灵方块（硬降落点的半透明投影）
+    pub show_ghost: bool,
+    /// 新游戏使用的操作手感和规则选项（进行中的游戏使用 `Game::settings`；
+    /// 模式、起始等级和随机器在开始游戏时由 `run_settings` 按所选模式填入）
+    pub rules: Settings,
+    /// 各模式使用的随机器
+    pub randomizers: ModeRandomizers,
+    /// 马拉松模式是否为无尽变体
+    pub marathon_endless: bool,
+    /// 马拉松模式的起始等级
+    pub start_level: u32,
+    /// 冲线模式的目标行数
+    pub sprint_lines: u32,
+    /// 限时模式的限定时间（秒）
+    pub ultra_seconds: u32,
 }
 
-impl Default for GameState {
+impl Default for GameSettings {
     fn default() -> Self {
-        let mut state = Self {
-            current_piece: None,
-            next_piece: TetrominoType::I, // 临时值，下面会被覆盖
-            score: 0,
-            game_over: false,
-            paused: false,
-            bag: Vec::new(),
-            lock_timer: None,
-        };
-        // 使用 7-Bag 系统生成初始方块
-        state.next_piece = state.next_from_bag();
-        state
+        Self {
+            preview_count: 5,
+            show_ghost: true,
+            rules: Settings::default(),
+            randomizers: ModeRandomizers::default(),
+            // 菜单默认的马拉松变体与核心的默认模式一致
+            marathon_endless: matches!(GameMode::default(), GameMode::Marathon { endless: true }),
+            start_level: 1,
+            sprint_lines: 40,
+            ultra_seconds: 120,
+        }
     }
 }
 
-impl GameState {
-    /// 重置游戏状态（用于重新开始游戏）
-    pub fn reset(&mut self) {
-        self.score = 0;
-        self.game_over = false;
-        self.paused = false;
-        self.current_piece = None;
-        self.bag.clear();
-        self.next_piece = self.next_from_bag();
-        self.lock_timer = None;
+impl GameSettings {
+    /// 获取以指定模式开始新游戏时使用的设置
+    /// 随机器按模式选取，起始等级只用于马拉松模式
+    pub fn run_settings(&self, mode: GameMode) -> Settings {
+        let start_level = match mode {
+            GameMode::Marathon { .. } => self.start_level,
+            _ => 1,
+        };
+        Settings {
+            mode,
+            start_level,
+            randomizer: self.randomizers.get(mode),
+            ..self.rules
+        }
     }
+}
 
-    /// 从 7-Bag 系统中获取下一个方块
-    ///
-    /// 7-Bag 系统确保每 7 个方块中包含所有 7 种类型各一个，
-    /// 避免了完全随机可能导致的长时间不出现某种方块的问题
-    pub fn next_from_bag(&mut self) -> TetrominoType {
-        use rand::seq::SliceRandom;
-
-        // 如果袋子空了，重新填充所有 7 种方块并打乱
-        if self.bag.is_empty() {
-            self.bag = vec![
-                TetrominoType::I,
-                TetrominoType::O,
-                TetrominoType::T,
-                TetrominoType::S,
-                TetrominoType::Z,
-                TetrominoType::J,
-                TetrominoType::L,
-            ];
-            self.bag.shuffle(&mut rand::rng());
+/// 各模式使用的随机器
+/// 同一模式的不同目标（如冲线 20 行和 40 行）共用一个随机器，便于按模式比较随机器
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ModeRandomizers {
+    /// 马拉松模式的随机器
+    pub marathon: RandomizerKind,
+    /// 冲线模式的随机器
+    pub sprint: RandomizerKind,
+    /// 限时模式的随机器
+    pub ultra: RandomizerKind,
+}
+
+impl ModeRandomizers {
+    /// 所有模式使用同一种随机器
+    pub fn all(kind: RandomizerKind) -> Self {
+        Self {
+            marathon: kind,
+            sprint: kind,
+            ultra: kind,
         }
+    }
 
-        // 从袋子中取出一个方块
-        self.bag.pop().unwrap()
+    /// 获取指定模式使用的随机器
+    pub fn get(&self, mode: GameMode) -> RandomizerKind {
+        match mode {
+            GameMode::Marathon { .. } => self.marathon,
+            GameMode::Sprint { .. } => self.sprint,
+            GameMode::Ultra { .. } => self.ultra,
+        }
     }
+}
 
-    /// 根据消行数量增加分数
-    ///
-    /// # 参数
-    /// * `lines_cleared` - 消行的数量（1-4行）
-    pub fn add_score(&mut self, lines_cleared: u32) {
-        self.score += match lines_cleared {
-            1 => SCORE_1_LINE,
-            2 => SCORE_2_LINES,
-            3 => SCORE_3_LINES,
-            4 => SCORE_4_LINES,
-            _ => 0,
-        };
+impl Default for ModeRandomizers {
+    fn default() -> Self {
+        Self::all(Settings::default().randomizer)
     }
 }
 
-/// 方块掉落计时器资源
-/// 控制方块的定时下落
-#[derive(Resource)]
-pub struct FallTimer {
-    /// Bevy 计时器，周期性触发
-    pub timer: Timer,
+/// 最高分记录中的一条成绩
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct HighScore {
+    /// 分数
+    pub score: u32,
+    /// 消除的行数
+    pub lines: u32,
+    /// 结束时的等级
+    pub level: u32,
+    /// 用时（帧）
+    pub frames: u32,
+    /// 放置的方块数
+    pub pieces: u32,
 }
 
-impl Default for FallTimer {
-    fn default() -> Self {
+impl HighScore {
+    /// 记录一局游戏的成绩
+    pub fn from_game(game: &Game) -> Self {
         Self {
-            timer: Timer::from_seconds(FALL_SPEED, TimerMode::Repeating),
+            score: game.score,
+            lines: game.lines,
+            level: game.level,
+            frames: game.frames,
+            pieces: game.pieces,
+        }
+    }
+
+    /// 判断在指定模式下本成绩是否优于另一成绩
+    /// 冲线模式比较用时，其余模式比较分数
+    pub fn beats(&self, other: &HighScore, mode: GameMode) -> bool {
+        match mode {
+            GameMode::Sprint { .. } => self.frames < other.frames,
+            GameMode::Marathon { .. } | GameMode::Ultra { .. } => self.score > other.score,
         }
     }
 }
+
+/// 最高分资源
+/// 按游戏模式分别保存最多 MAX_ENTRIES 条成绩
+#[derive(Resource, Default)]
+pub struct HighScores {
+    /// 各模式的成绩列表（按成绩从好到差排列）
+    pub tables: HashMap<GameMode, Vec<HighScore>>,
+}
+
+impl HighScores {
+    /// 每个模式保留的成绩条数
+    pub const MAX_ENTRIES: usize = 10;
+
+    /// 获取指定模式的成绩列表
+    pub fn entries(&self, mode: GameMode) -> &[HighScore] {
+        self.tables.get(&mode).map_or(&[], Vec::as_slice)
+    }
+
+    /// 记录一条成绩
+    ///
+    /// # 返回
+    /// 成绩进入记录时返回名次（从 0 开始），未进入时返回 None
+    pub fn insert(&mut self, mode: GameMode, entry: HighScore) -> Option<usize> {
+        let entries = self.tables.entry(mode).or_default();
+        // 成绩相同时先取得的成绩排在前面
+        let rank = entries
+            .iter()
+            .position(|existing| entry.beats(existing, mode))
+            .unwrap_or(entries.len());
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+        entries.insert(rank, entry);
+        entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// 最高分页面当前显示的模式（在 `ranked_modes` 中的下标）
+#[derive(Resource, Default)]
+pub struct HighScoreView(pub usize);
+
+/// 菜单光标资源
+/// 当前菜单页面中选中的条目，切换页面时回到第一项
+#[derive(Resource, Default)]
+pub struct MenuCursor(pub usize);
+
+/// 回放列表资源
+/// 进入回放列表页面时读取的回放文件路径（从新到旧排列）
+#[derive(Resource, Default)]
+pub struct ReplayList {
+    /// 回放文件路径
+    pub paths: Vec<String>,
+    /// 最近一次加载失败的回放文件路径
+    pub failed: Option<String>,
+}
+
+/// 玩家输入资源
+/// 由键盘映射得到的操作键状态，交给固定帧率的游戏逻辑系统推进模拟
+#[derive(Resource, Default)]
+pub struct PlayerInputs {
+    /// 当前按住的操作键
+    pub held: Inputs,
+    /// 下一个固定帧要使用的输入（自上一个固定帧以来按住过的所有键）
+    pub frame: Inputs,
+}
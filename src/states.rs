@@ -0,0 +1,53 @@
+// 应用状态模块：定义驱动游戏流程的 Bevy 状态
+
+use bevy::prelude::*;
+
+/// 应用状态
+/// 决定当前运行哪些系统：主菜单、游戏进行中、暂停、游戏结束或回放播放
+#[derive(States, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum AppState {
+    /// 主菜单
+    #[default]
+    MainMenu,
+    /// 游戏进行中
+    Playing,
+    /// 游戏暂停
+    Paused,
+    /// 游戏结束，等待重新开始或返回主菜单
+    GameOver,
+    /// 播放回放
+    Replay,
+}
+
+/// 游戏画面状态
+/// 由 `AppState` 计算得出，除主菜单外的状态都显示游戏板和游戏信息，
+/// 用于统一创建和销毁游戏画面
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = AppState;
+
+    fn compute(state: AppState) -> Option<Self> {
+        match state {
+            AppState::MainMenu => None,
+            _ => Some(InGame),
+        }
+    }
+}
+
+/// 主菜单页面
+/// 只在主菜单状态下存在，每次进入主菜单时回到首页
+#[derive(SubStates, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[source(AppState = AppState::MainMenu)]
+pub enum MenuPage {
+    /// 首页：游戏模式和其他页面的入口
+    #[default]
+    Main,
+    /// 设置
+    Settings,
+    /// 最高分
+    HighScores,
+    /// 回放列表
+    Replays,
+}
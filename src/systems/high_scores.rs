@@ -0,0 +1,108 @@
+// 最高分系统
+// 保存和加载最高分记录，并在游戏结束时记录成绩
+
+use crate::resources::{GameState, HighScore, HighScores};
+use bevy::prelude::*;
+use std::{fs, io};
+use tetris_core::GameMode;
+
+/// 最高分记录文件
+const HIGH_SCORE_FILE: &str = "highscores.txt";
+
+/// 最高分文件的首行（格式标识）
+const HEADER: &str = "tetris-highscores 1";
+
+/// 获取记录最高分的所有模式（最高分页面按此顺序切换）
+pub fn ranked_modes() -> Vec<GameMode> {
+    let sprints = GameMode::SPRINT_LINES
+        .iter()
+        .map(|&lines| GameMode::Sprint { lines });
+    let ultras = GameMode::ULTRA_SECONDS
+        .iter()
+        .map(|&seconds| GameMode::Ultra { seconds });
+    [
+        GameMode::Marathon { endless: false },
+        GameMode::Marathon { endless: true },
+    ]
+    .into_iter()
+    .chain(sprints)
+    .chain(ultras)
+    .collect()
+}
+
+/// 从文件加载最高分记录
+///
+/// 文件不存在或无法解析的行会被忽略，不影响游戏启动
+pub fn load_high_scores() -> HighScores {
+    let mut high_scores = HighScores::default();
+    let Ok(text) = fs::read_to_string(HIGH_SCORE_FILE) else {
+        return high_scores;
+    };
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return high_scores;
+    }
+    for line in lines {
+        let mut fields = line.split_whitespace();
+        let Some(mode) = fields.next().and_then(GameMode::from_name) else {
+            continue;
+        };
+        // 任意字段无法解析或字段数不符时跳过整行
+        let Ok(numbers) = fields.map(str::parse).collect::<Result<Vec<u32>, _>>() else {
+            continue;
+        };
+        let [score, lines, level, frames, pieces] = numbers[..] else {
+            continue;
+        };
+        high_scores.insert(
+            mode,
+            HighScore {
+                score,
+                lines,
+                level,
+                frames,
+                pieces,
+            },
+        );
+    }
+    high_scores
+}
+
+/// 将最高分记录保存到文件
+///
+/// 每条成绩占一行，依次为模式名称、分数、行数、等级、用时（帧）和方块数
+pub fn save_high_scores(high_scores: &HighScores) -> io::Result<()> {
+    let mut text = format!("{HEADER}\n");
+    for mode in ranked_modes() {
+        for entry in high_scores.entries(mode) {
+            text.push_str(&format!(
+                "{} {} {} {} {} {}\n",
+                mode.name(),
+                entry.score,
+                entry.lines,
+                entry.level,
+                entry.frames,
+                entry.pieces
+            ));
+        }
+    }
+    fs::write(HIGH_SCORE_FILE, text)
+}
+
+/// 记录本局成绩
+/// 进入游戏结束状态时调用，成绩进入最高分记录时保存到文件。
+/// 冲线和限时模式只记录达成目标（消除目标行数或坚持到时间结束）的成绩
+pub fn record_high_score(game_state: Res<GameState>, mut high_scores: ResMut<HighScores>) {
+    let game = &game_state.game;
+    let mode = game.settings.mode;
+    if mode.requires_completion() && !game.completed {
+        return;
+    }
+    if high_scores
+        .insert(mode, HighScore::from_game(game))
+        .is_some()
+        && let Err(error) = save_high_scores(&high_scores)
+    {
+        warn!("无法保存最高分记录：{error}");
+    }
+}
@@ -0,0 +1,637 @@
+// 主菜单系统
+// 创建主菜单各页面，处理键盘和手柄的菜单导航
+
+use crate::components::{MenuItem, MenuNote};
+use crate::resources::{
+    GameSettings, GameState, HighScoreView, HighScores, MenuCursor, ReplayList, ReplayPlayback,
+    SeedConfig,
+};
+use crate::states::{AppState, MenuPage};
+use crate::systems::high_scores::ranked_modes;
+use crate::systems::rendering::format_ms;
+use crate::systems::replay::{list_replays, load_replay};
+use bevy::prelude::*;
+use tetris_core::constants::{MARATHON_LINES, MAX_PREVIEW_COUNT, MAX_START_LEVEL, frames_to_ms};
+use tetris_core::{GameMode, LockDelayMode, RandomizerKind, SoftDropSpeed};
+
+/// 菜单操作（由键盘或手柄按键得出）
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MenuAction {
+    /// 选择上一项
+    Up,
+    /// 选择下一项
+    Down,
+    /// 减小当前选项的值
+    Left,
+    /// 增大当前选项的值
+    Right,
+    /// 确认
+    Confirm,
+    /// 返回上一级
+    Back,
+}
+
+/// 首页条目
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MainItem {
+    /// 开始马拉松模式（左右切换 150 行目标和无尽变体）
+    Marathon,
+    /// 马拉松模式的起始等级（左右调整，确认同样开始马拉松模式）
+    StartLevel,
+    /// 开始冲线模式（左右调整目标行数）
+    Sprint,
+    /// 开始限时模式（左右调整限定时间）
+    Ultra,
+    /// 设置页面
+    Settings,
+    /// 最高分页面
+    HighScores,
+    /// 回放列表页面
+    Replays,
+    /// 退出游戏
+    Quit,
+}
+
+/// 首页条目（按显示顺序排列）
+const MAIN_ITEMS: [MainItem; 8] = [
+    MainItem::Marathon,
+    MainItem::StartLevel,
+    MainItem::Sprint,
+    MainItem::Ultra,
+    MainItem::Settings,
+    MainItem::HighScores,
+    MainItem::Replays,
+    MainItem::Quit,
+];
+
+/// 最高分页面的条目数（模式选择和返回）
+const HIGH_SCORE_ITEMS: usize = 2;
+
+/// 设置页面的条目数（最后一项为返回）
+const SETTINGS_ITEMS: usize = 15;
+
+/// 可选的软降速度
+const SOFT_DROP_SPEEDS: [SoftDropSpeed; 5] = [
+    SoftDropSpeed::Factor(5),
+    SoftDropSpeed::Factor(10),
+    SoftDropSpeed::Factor(20),
+    SoftDropSpeed::Factor(40),
+    SoftDropSpeed::Instant,
+];
+
+/// 可选的锁定延迟模式
+const LOCK_DELAY_MODES: [LockDelayMode; 3] = [
+    LockDelayMode::Infinite,
+    LockDelayMode::MoveReset,
+    LockDelayMode::StepReset,
+];
+
+/// 回放列表一次最多显示的条目数
+const VISIBLE_REPLAYS: usize = 10;
+
+/// 条目数超过该值的页面使用紧凑排版（缩小字号和间距），使所有条目都能放入窗口
+const COMPACT_PAGE_SLOTS: usize = 12;
+
+/// 选中条目的颜色
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.8, 0.2); // 金色
+
+/// 创建首页
+pub fn setup_main_page(mut commands: Commands, mut cursor: ResMut<MenuCursor>) {
+    cursor.0 = 0;
+    spawn_page(&mut commands, MenuPage::Main, "TETRIS", MAIN_ITEMS.len());
+}
+
+/// 创建设置页面
+pub fn setup_settings_page(mut commands: Commands, mut cursor: ResMut<MenuCursor>) {
+    cursor.0 = 0;
+    spawn_page(
+        &mut commands,
+        MenuPage::Settings,
+        "SETTINGS",
+        SETTINGS_ITEMS,
+    );
+}
+
+/// 创建最高分页面
+pub fn setup_high_scores_page(mut commands: Commands, mut cursor: ResMut<MenuCursor>) {
+    cursor.0 = 0;
+    spawn_page(
+        &mut commands,
+        MenuPage::HighScores,
+        "HIGH SCORES",
+        HIGH_SCORE_ITEMS,
+    );
+}
+
+/// 创建回放列表页面
+/// 每次进入时重新读取回放目录
+pub fn setup_replays_page(
+    mut commands: Commands,
+    mut cursor: ResMut<MenuCursor>,
+    mut replays: ResMut<ReplayList>,
+) {
+    cursor.0 = 0;
+    replays.paths = list_replays();
+    replays.failed = None;
+    // 回放条目之后还有一个返回条目
+    let slots = replays.paths.len().min(VISIBLE_REPLAYS) + 1;
+    spawn_page(&mut commands, MenuPage::Replays, "REPLAYS", slots);
+}
+
+/// 创建菜单页面的通用布局：标题、说明文本、若干条目和底部的操作提示
+///
+/// # 参数
+/// * `slots` - 条目文本的数量，条目和说明文本的内容由 `update_menu_items` 每帧填写
+fn spawn_page(commands: &mut Commands, page: MenuPage, title: &str, slots: usize) {
+    let compact = slots > COMPACT_PAGE_SLOTS;
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(if compact { 4.0 } else { 12.0 }),
+                ..default()
+            },
+            DespawnOnExit(page),
+        ))
+        .with_children(|parent| {
+            // 页面标题
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 64.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.0, 0.9, 0.9)), // 青色
+                Node {
+                    margin: UiRect::bottom(Val::Px(24.0)),
+                    ..default()
+                },
+            ));
+            // 说明文本（如最高分表格）
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(16.0)),
+                    ..default()
+                },
+                MenuNote,
+            ));
+            // 可选条目
+            for slot in 0..slots {
+                parent.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: if compact { 22.0 } else { 28.0 },
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    MenuItem(slot),
+                ));
+            }
+            // 操作提示（回放列表没有可以左右调整的条目）
+            let hint = if page != MenuPage::Replays {
+                "\u{2191}/\u{2193}: Select  \u{2190}/\u{2192}: Change  ENTER: Confirm  ESC: Back"
+            } else {
+                "\u{2191}/\u{2193}: Select  ENTER: Confirm  ESC: Back"
+            };
+            parent.spawn((
+                Text::new(hint),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                Node {
+                    margin: UiRect::top(Val::Px(24.0)),
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// 菜单条目更新系统
+/// 根据当前页面和设置填写说明文本和各条目的文本，选中的条目高亮显示；
+/// 条目多于显示位置时，显示范围随光标滚动
+#[allow(clippy::too_many_arguments)]
+pub fn update_menu_items(
+    page: Res<State<MenuPage>>,
+    cursor: Res<MenuCursor>,
+    settings: Res<GameSettings>,
+    replays: Res<ReplayList>,
+    high_scores: Res<HighScores>,
+    view: Res<HighScoreView>,
+    mut notes: Query<&mut Text, (With<MenuNote>, Without<MenuItem>)>,
+    mut query: Query<(&mut Text, &mut TextColor, &MenuItem)>,
+) {
+    let page = *page.get();
+    let view_mode = ranked_modes()[view.0];
+    for mut text in notes.iter_mut() {
+        **text = match page {
+            MenuPage::HighScores => high_score_table(&high_scores, view_mode),
+            MenuPage::Replays if replays.paths.is_empty() => "No replays".to_string(),
+            _ => String::new(),
+        };
+    }
+
+    let items = page_items(page, &settings, &replays, view_mode);
+    let slots = query.iter().count();
+    let first = (cursor.0 + 1).saturating_sub(slots);
+    for (mut text, mut color, &MenuItem(slot)) in query.iter_mut() {
+        let index = first + slot;
+        let label = items.get(index).cloned().unwrap_or_default();
+        if index == cursor.0 {
+            **text = format!("> {label} <");
+            color.0 = SELECTED_COLOR;
+        } else {
+            **text = label;
+            color.0 = Color::WHITE;
+        }
+    }
+}
+
+/// 获取页面的全部条目文本
+fn page_items(
+    page: MenuPage,
+    settings: &GameSettings,
+    replays: &ReplayList,
+    view_mode: GameMode,
+) -> Vec<String> {
+    match page {
+        MenuPage::Main => MAIN_ITEMS
+            .iter()
+            .map(|item| match item {
+                MainItem::Marathon => {
+                    if settings.marathon_endless {
+                        "Marathon: \u{2190} Endless \u{2192}".to_string()
+                    } else {
+                        format!("Marathon: \u{2190} {MARATHON_LINES} lines \u{2192}")
+                    }
+                }
+                MainItem::StartLevel => {
+                    format!("Start level: \u{2190} {} \u{2192}", settings.start_level)
+                }
+                MainItem::Sprint => {
+                    format!("Sprint: \u{2190} {} lines \u{2192}", settings.sprint_lines)
+                }
+                MainItem::Ultra => format!(
+                    "Ultra: \u{2190} {}:{:02} \u{2192}",
+                    settings.ultra_seconds / 60,
+                    settings.ultra_seconds % 60
+                ),
+                MainItem::Settings => "Settings".to_string(),
+                MainItem::HighScores => "High Scores".to_string(),
+                MainItem::Replays => "Replays".to_string(),
+                MainItem::Quit => "Quit".to_string(),
+            })
+            .collect(),
+        MenuPage::Settings => {
+            let rules = &settings.rules;
+            let randomizers = &settings.randomizers;
+            let soft_drop = match rules.soft_drop {
+                SoftDropSpeed::Factor(factor) => format!("x{factor}"),
+                SoftDropSpeed::Instant => "Instant".to_string(),
+            };
+            let lock_delay = match rules.lock_delay_mode {
+                LockDelayMode::Infinite => "Infinite",
+                LockDelayMode::MoveReset => "Move reset",
+                LockDelayMode::StepReset => "Step reset",
+            };
+            vec![
+                format!("Marathon randomizer: {}", randomizers.marathon.name()),
+                format!("Sprint randomizer: {}", randomizers.sprint.name()),
+                format!("Ultra randomizer: {}", randomizers.ultra.name()),
+                format!("DAS: {} ms", rules.das_ms),
+                format!("ARR: {} ms", rules.arr_ms),
+                format!("Soft drop: {soft_drop}"),
+                format!("Lock delay: {lock_delay}"),
+                format!("Entry delay: {} ms", rules.entry_delay_ms),
+                format!("Line clear delay: {} ms", rules.line_clear_delay_ms),
+                format!("Block out: {}", on_off(rules.top_out.block_out)),
+                format!("Lock out: {}", on_off(rules.top_out.lock_out)),
+                format!(
+                    "Partial lock out: {}",
+                    on_off(rules.top_out.partial_lock_out)
+                ),
+                format!("Previews: {}", settings.preview_count),
+                format!("Ghost piece: {}", on_off(settings.show_ghost)),
+                "Back".to_string(),
+            ]
+        }
+        MenuPage::HighScores => vec![
+            format!("\u{2190} {} \u{2192}", view_mode.label()),
+            "Back".to_string(),
+        ],
+        MenuPage::Replays => {
+            let mut items: Vec<String> = replays
+                .paths
+                .iter()
+                .map(|path| {
+                    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+                    if replays.failed.as_deref() == Some(path.as_str()) {
+                        format!("{name} (failed to load)")
+                    } else {
+                        name.to_string()
+                    }
+                })
+                .collect();
+            items.push("Back".to_string());
+            items
+        }
+    }
+}
+
+/// 获取指定模式的最高分表格文本
+/// 冲线模式按用时排列，其余模式按分数排列
+fn high_score_table(high_scores: &HighScores, mode: GameMode) -> String {
+    let entries = high_scores.entries(mode);
+    if entries.is_empty() {
+        return "No records yet".to_string();
+    }
+    entries
+        .iter()
+        .enumerate()
+        .map(|(rank, entry)| match mode {
+            GameMode::Sprint { .. } => format!(
+                "{:>2}. {:>10}  {:>4} pieces",
+                rank + 1,
+                format_ms(frames_to_ms(entry.frames)),
+                entry.pieces
+            ),
+            GameMode::Marathon { .. } | GameMode::Ultra { .. } => format!(
+                "{:>2}. {:>8}  {:>4} lines  Lv {}",
+                rank + 1,
+                entry.score,
+                entry.lines,
+                entry.level
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 读取本帧的菜单操作
+///
+/// 键盘：方向键选择和调整，回车确认，ESC/退格返回；
+/// 手柄：方向键选择和调整，South（A）确认，East（B）返回
+fn menu_action(keyboard: &ButtonInput<KeyCode>, gamepads: &Query<&Gamepad>) -> Option<MenuAction> {
+    let bindings: [(MenuAction, &[KeyCode], GamepadButton); 6] = [
+        (MenuAction::Up, &[KeyCode::ArrowUp], GamepadButton::DPadUp),
+        (
+            MenuAction::Down,
+            &[KeyCode::ArrowDown],
+            GamepadButton::DPadDown,
+        ),
+        (
+            MenuAction::Left,
+            &[KeyCode::ArrowLeft],
+            GamepadButton::DPadLeft,
+        ),
+        (
+            MenuAction::Right,
+            &[KeyCode::ArrowRight],
+            GamepadButton::DPadRight,
+        ),
+        (MenuAction::Confirm, &[KeyCode::Enter], GamepadButton::South),
+        (
+            MenuAction::Back,
+            &[KeyCode::Escape, KeyCode::Backspace],
+            GamepadButton::East,
+        ),
+    ];
+    bindings
+        .into_iter()
+        .find(|(_, keys, button)| {
+            keyboard.any_just_pressed(keys.iter().copied())
+                || gamepads.iter().any(|gamepad| gamepad.just_pressed(*button))
+        })
+        .map(|(action, _, _)| action)
+}
+
+/// 主菜单输入处理系统
+/// 上下移动光标，并按当前页面处理确认、返回和数值调整
+#[allow(clippy::too_many_arguments)]
+pub fn handle_menu_input(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    page: Res<State<MenuPage>>,
+    mut next_page: ResMut<NextState<MenuPage>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut cursor: ResMut<MenuCursor>,
+    mut settings: ResMut<GameSettings>,
+    mut replays: ResMut<ReplayList>,
+    mut view: ResMut<HighScoreView>,
+    mut game_state: ResMut<GameState>,
+    seed_config: Res<SeedConfig>,
+    mut app_exit_events: MessageWriter<AppExit>,
+) {
+    let Some(action) = menu_action(&keyboard, &gamepads) else {
+        return;
+    };
+
+    // 光标在首尾之间循环移动
+    let count = match page.get() {
+        MenuPage::Main => MAIN_ITEMS.len(),
+        MenuPage::Settings => SETTINGS_ITEMS,
+        MenuPage::HighScores => HIGH_SCORE_ITEMS,
+        MenuPage::Replays => replays.paths.len() + 1,
+    };
+    match action {
+        MenuAction::Up => {
+            cursor.0 = (cursor.0 + count - 1) % count;
+            return;
+        }
+        MenuAction::Down => {
+            cursor.0 = (cursor.0 + 1) % count;
+            return;
+        }
+        _ => {}
+    }
+
+    match page.get() {
+        MenuPage::Main => {
+            let item = MAIN_ITEMS[cursor.0];
+            // 开始游戏时使用设置页面的规则和所选的模式，起始等级只用于马拉松模式
+            let mode = match item {
+                MainItem::Marathon | MainItem::StartLevel => Some(GameMode::Marathon {
+                    endless: settings.marathon_endless,
+                }),
+                MainItem::Sprint => Some(GameMode::Sprint {
+                    lines: settings.sprint_lines,
+                }),
+                MainItem::Ultra => Some(GameMode::Ultra {
+                    seconds: settings.ultra_seconds,
+                }),
+                _ => None,
+            };
+            match (action, item) {
+                (MenuAction::Left | MenuAction::Right, MainItem::Marathon) => {
+                    settings.marathon_endless = !settings.marathon_endless;
+                }
+                (MenuAction::Left | MenuAction::Right, MainItem::StartLevel) => {
+                    let direction = if action == MenuAction::Left { -1 } else { 1 };
+                    let index = cycle(
+                        settings.start_level as usize - 1,
+                        direction,
+                        MAX_START_LEVEL as usize,
+                    );
+                    settings.start_level = index as u32 + 1;
+                }
+                (MenuAction::Left | MenuAction::Right, MainItem::Sprint) => {
+                    let direction = if action == MenuAction::Left { -1 } else { 1 };
+                    let choices = &GameMode::SPRINT_LINES;
+                    let index = choices
+                        .iter()
+                        .position(|&lines| lines == settings.sprint_lines)
+                        .unwrap_or(0);
+                    settings.sprint_lines = choices[cycle(index, direction, choices.len())];
+                }
+                (MenuAction::Left | MenuAction::Right, MainItem::Ultra) => {
+                    let direction = if action == MenuAction::Left { -1 } else { 1 };
+                    let choices = &GameMode::ULTRA_SECONDS;
+                    let index = choices
+                        .iter()
+                        .position(|&seconds| seconds == settings.ultra_seconds)
+                        .unwrap_or(0);
+                    settings.ultra_seconds = choices[cycle(index, direction, choices.len())];
+                }
+                (MenuAction::Confirm, MainItem::Settings) => next_page.set(MenuPage::Settings),
+                (MenuAction::Confirm, MainItem::HighScores) => next_page.set(MenuPage::HighScores),
+                (MenuAction::Confirm, MainItem::Replays) => next_page.set(MenuPage::Replays),
+                (MenuAction::Confirm, MainItem::Quit) => {
+                    app_exit_events.write(AppExit::Success);
+                }
+                (MenuAction::Confirm, _) => {
+                    if let Some(mode) = mode {
+                        let rules = settings.run_settings(mode);
+                        *game_state = GameState::new(rules, seed_config.next_seed());
+                        next_state.set(AppState::Playing);
+                    }
+                }
+                _ => {}
+            }
+        }
+        MenuPage::Settings => match action {
+            MenuAction::Left => change_setting(&mut settings, cursor.0, -1),
+            MenuAction::Right => change_setting(&mut settings, cursor.0, 1),
+            MenuAction::Confirm if cursor.0 == SETTINGS_ITEMS - 1 => next_page.set(MenuPage::Main),
+            MenuAction::Confirm => change_setting(&mut settings, cursor.0, 1),
+            MenuAction::Back => next_page.set(MenuPage::Main),
+            _ => {}
+        },
+        MenuPage::HighScores => match action {
+            MenuAction::Left => view.0 = cycle(view.0, -1, ranked_modes().len()),
+            MenuAction::Right => view.0 = cycle(view.0, 1, ranked_modes().len()),
+            MenuAction::Confirm if cursor.0 == 0 => {
+                view.0 = cycle(view.0, 1, ranked_modes().len());
+            }
+            MenuAction::Confirm | MenuAction::Back => next_page.set(MenuPage::Main),
+            _ => {}
+        },
+        MenuPage::Replays => match action {
+            MenuAction::Confirm if cursor.0 < replays.paths.len() => {
+                let path = replays.paths[cursor.0].clone();
+                match load_replay(&path) {
+                    Ok(replay) => {
+                        *game_state = GameState::new(replay.settings, replay.seed);
+                        commands.insert_resource(ReplayPlayback::new(replay));
+                        next_state.set(AppState::Replay);
+                    }
+                    Err(error) => {
+                        warn!("{error}");
+                        replays.failed = Some(path);
+                    }
+                }
+            }
+            MenuAction::Confirm | MenuAction::Back => next_page.set(MenuPage::Main),
+            _ => {}
+        },
+    }
+}
+
+/// 调整设置页面中的一项设置
+///
+/// # 参数
+/// * `item` - 设置条目的下标
+/// * `direction` - 调整方向（-1 减小，1 增大；开关和列表类选项循环切换）
+fn change_setting(settings: &mut GameSettings, item: usize, direction: i32) {
+    let randomizers = &mut settings.randomizers;
+    let randomizer = match item {
+        0 => Some(&mut randomizers.marathon),
+        1 => Some(&mut randomizers.sprint),
+        2 => Some(&mut randomizers.ultra),
+        _ => None,
+    };
+    if let Some(randomizer) = randomizer {
+        let kinds = &RandomizerKind::ALL;
+        let index = kinds
+            .iter()
+            .position(|&kind| kind == *randomizer)
+            .unwrap_or(0);
+        *randomizer = kinds[cycle(index, direction, kinds.len())];
+        return;
+    }
+
+    let rules = &mut settings.rules;
+    match item {
+        3 => rules.das_ms = rules.das_ms.saturating_add_signed(direction * 10).min(500),
+        4 => rules.arr_ms = rules.arr_ms.saturating_add_signed(direction * 5).min(200),
+        5 => {
+            let index = SOFT_DROP_SPEEDS
+                .iter()
+                .position(|&speed| speed == rules.soft_drop)
+                .unwrap_or(0);
+            rules.soft_drop = SOFT_DROP_SPEEDS[cycle(index, direction, SOFT_DROP_SPEEDS.len())];
+        }
+        6 => {
+            let index = LOCK_DELAY_MODES
+                .iter()
+                .position(|&mode| mode == rules.lock_delay_mode)
+                .unwrap_or(0);
+            rules.lock_delay_mode =
+                LOCK_DELAY_MODES[cycle(index, direction, LOCK_DELAY_MODES.len())];
+        }
+        7 => {
+            rules.entry_delay_ms = rules
+                .entry_delay_ms
+                .saturating_add_signed(direction * 10)
+                .min(500);
+        }
+        8 => {
+            rules.line_clear_delay_ms = rules
+                .line_clear_delay_ms
+                .saturating_add_signed(direction * 50)
+                .min(1000);
+        }
+        9 => rules.top_out.block_out = !rules.top_out.block_out,
+        10 => rules.top_out.lock_out = !rules.top_out.lock_out,
+        11 => rules.top_out.partial_lock_out = !rules.top_out.partial_lock_out,
+        12 => {
+            settings.preview_count = settings
+                .preview_count
+                .saturating_add_signed(direction as isize)
+                .clamp(1, MAX_PREVIEW_COUNT);
+        }
+        13 => settings.show_ghost = !settings.show_ghost,
+        _ => {}
+    }
+}
+
+/// 获取开关类选项的显示文字
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "On" } else { "Off" }
+}
+
+/// 在长度为 `len` 的列表中按方向循环移动下标
+fn cycle(index: usize, direction: i32, len: usize) -> usize {
+    (index as i32 + direction).rem_euclid(len as i32) as usize
+}
@@ -2,10 +2,21 @@
 // 包含游戏逻辑、输入处理和渲染系统
 
 mod game; // 游戏核心逻辑（下落、碰撞、消行）
+mod high_scores; // 最高分记录
+mod menu; // 主菜单（模式选择、设置、最高分、回放列表）
 mod player_input; // 玩家输入处理
 mod rendering; // 游戏渲染和 UI 更新
+mod replay; // 回放保存、加载和播放
 
 // 公共导出接口
-pub use game::update_game_logic;
-pub use player_input::handle_player_input;
+pub use game::{save_recording, update_game_logic};
+pub use high_scores::{load_high_scores, record_high_score};
+pub use menu::{
+    handle_menu_input, setup_high_scores_page, setup_main_page, setup_replays_page,
+    setup_settings_page, update_menu_items,
+};
+pub use player_input::{
+    handle_game_over_input, handle_pause_input, handle_player_input, reset_player_inputs,
+};
 pub use rendering::{render_game, update_ui};
+pub use replay::{end_replay, handle_replay_input, load_replay, update_replay};
@@ -1,89 +1,86 @@
 // 玩家输入处理系统
-// 处理键盘输入，实现方块的移动、旋转和游戏控制
+// 处理键盘输入，将操作键映射为模拟核心的输入，并处理游戏控制
 
-use crate::resources::{GameBoard, GameState};
+use crate::resources::{GameSettings, GameState, PlayerInputs, SeedConfig};
+use crate::states::AppState;
 use bevy::prelude::*;
+use tetris_core::Inputs;
 
-/// 玩家输入处理系统
+/// 玩家输入处理系统（游戏进行中）
 /// 处理所有键盘输入，包括：
-/// - 方块移动（方向键/WASD）
-/// - 方块旋转（上箭头/W）
-/// - 快速下落（下箭头/S）
+/// - 方块移动（左右方向键）
+/// - 方块旋转（上箭头/X 顺时针，Z 逆时针，A 180°）
+/// - 快速下落（下箭头）
 /// - 直接落地（空格）
-/// - 暂停/继续（ESC）
-/// - 游戏结束后重新开始（空格）
-/// - 退出游戏（Q）
+/// - 暂存方块（C/Shift）
+/// - 暂停（ESC）
+/// - 切换幽灵方块显示（G）
+///
+/// 方块操作只记录按住状态，DAS/ARR、软降速度和按键触发时机由模拟核心处理
 pub fn handle_player_input(
     keyboard: Res<ButtonInput<KeyCode>>,
-    mut game_state: ResMut<GameState>,
-    mut board: ResMut<GameBoard>,
-    mut app_exit_events: MessageWriter<AppExit>,
+    mut inputs: ResMut<PlayerInputs>,
+    mut settings: ResMut<GameSettings>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
-    // 处理暂停/继续（ESC 键）
-    if keyboard.just_pressed(KeyCode::Escape) && !game_state.game_over {
-        game_state.paused = !game_state.paused;
-        return;
-    }
-
-    // 处理退出游戏（Q 键）
-    if keyboard.just_pressed(KeyCode::KeyQ) {
-        app_exit_events.write(AppExit::Success);
-        return;
-    }
+    // 读取当前按住的操作键，并累计到下一个固定帧的输入中，
+    // 避免在两个固定帧之间按下又松开的键被漏掉
+    inputs.held = Inputs {
+        left: keyboard.pressed(KeyCode::ArrowLeft),
+        right: keyboard.pressed(KeyCode::ArrowRight),
+        soft_drop: keyboard.pressed(KeyCode::ArrowDown),
+        hard_drop: keyboard.pressed(KeyCode::Space),
+        rotate_cw: keyboard.any_pressed([KeyCode::ArrowUp, KeyCode::KeyX]),
+        rotate_ccw: keyboard.pressed(KeyCode::KeyZ),
+        rotate_180: keyboard.pressed(KeyCode::KeyA),
+        hold: keyboard.any_pressed([KeyCode::KeyC, KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+    };
+    inputs.frame = inputs.frame.union(&inputs.held);
 
-    // 游戏结束时的处理
-    if game_state.game_over {
-        // 按空格重新开始游戏
-        if keyboard.just_pressed(KeyCode::Space) {
-            game_state.reset();
-            *board = GameBoard::default();
-        }
-        return;
+    // 暂停游戏（ESC 键）
+    if keyboard.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::Paused);
     }
 
-    // 游戏暂停时不处理方块操作
-    if game_state.paused {
-        return;
+    // 切换幽灵方块显示（G 键）
+    if keyboard.just_pressed(KeyCode::KeyG) {
+        settings.show_ghost = !settings.show_ghost;
     }
+}
 
-    // 处理活跃方块的操作
-    if let Some(piece) = game_state.current_piece {
-        let mut new_piece = piece;
+/// 清空玩家输入
+/// 离开游戏进行状态时调用，避免暂停前按住的键在继续游戏后的第一帧仍被当作按住
+pub fn reset_player_inputs(mut inputs: ResMut<PlayerInputs>) {
+    *inputs = PlayerInputs::default();
+}
 
-        // 判断按下了哪个移动/操作键
-        if keyboard.just_pressed(KeyCode::ArrowLeft) || keyboard.just_pressed(KeyCode::KeyA) {
-            // 左移：X 坐标减 1
-            new_piece.x -= 1;
-        } else if keyboard.just_pressed(KeyCode::ArrowRight) || keyboard.just_pressed(KeyCode::KeyD)
-        {
-            // 右移：X 坐标加 1
-            new_piece.x += 1;
-        } else if keyboard.just_pressed(KeyCode::ArrowDown) || keyboard.just_pressed(KeyCode::KeyS)
-        {
-            // 快速下落：Y 坐标加 1
-            new_piece.y += 1;
-        } else if keyboard.just_pressed(KeyCode::ArrowUp) || keyboard.just_pressed(KeyCode::KeyW) {
-            // 旋转：旋转角度加 1（0-3 循环）
-            new_piece.rotation = (new_piece.rotation + 1) % 4;
-        } else if keyboard.just_pressed(KeyCode::Space) {
-            // 直接落地：不断下移直到碰撞
-            while !new_piece.check_collision(0, 1, &board) {
-                new_piece.y += 1;
-            }
-        } else {
-            // 没有按下任何操作键，直接返回
-            return;
-        }
+/// 暂停输入处理系统
+/// - 继续游戏（ESC）
+/// - 返回主菜单（Q）
+pub fn handle_pause_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::Playing);
+    } else if keyboard.just_pressed(KeyCode::KeyQ) {
+        next_state.set(AppState::MainMenu);
+    }
+}
 
-        // 检查新位置是否有效（不与已固定方块碰撞）
-        if !new_piece.check_collision(0, 0, &board) {
-            // 有效，更新当前方块
-            game_state.current_piece = Some(new_piece);
-            // 如果移动后脱离触底状态，重置锁定计时器
-            if !new_piece.check_collision(0, 1, &board) {
-                game_state.lock_timer = None;
-            }
-        }
-        // 如果碰撞，则不更新方块位置（玩家操作被拒绝）
+/// 游戏结束输入处理系统
+/// - 重新开始（空格，未指定固定种子时使用新的种子）
+/// - 返回主菜单（Q）
+pub fn handle_game_over_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    seed_config: Res<SeedConfig>,
+    mut game_state: ResMut<GameState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        game_state.restart(seed_config.next_seed());
+        next_state.set(AppState::Playing);
+    } else if keyboard.just_pressed(KeyCode::KeyQ) {
+        next_state.set(AppState::MainMenu);
     }
 }
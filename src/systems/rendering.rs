@@ -3,33 +3,44 @@
 
 use crate::components::{Block, UiText};
 use crate::constants::*;
-use crate::resources::{GameBoard, GameState};
+use crate::resources::{GameSettings, GameState, ReplayPlayback};
+use crate::states::{AppState, InGame};
 use bevy::prelude::*;
+use tetris_core::constants::{
+    BUFFER_HEIGHT, GRAVITY_UNIT, GRID_HEIGHT, GRID_WIDTH, MAX_PREVIEW_COUNT, SPLIT_LINES,
+    TOTAL_HEIGHT, frames_to_ms, gravity, ms_to_frames,
+};
+use tetris_core::{Game, GameMode, GamePhase, TetrominoType};
 
 /// 游戏渲染系统
 /// 每帧清除上一帧的所有方块实体，然后重新绘制：
-/// 1. 游戏板背景网格
-/// 2. 已锁定的方块
-/// 3. 当前下落的方块
-/// 4. 下一个方块的预览
+/// 1. 游戏板可见区域的背景网格
+/// 2. 已锁定的方块（以及可见区域上方露出的一行缓冲区）和消行高亮
+/// 3. 幽灵方块和当前下落的方块
+/// 4. 后续方块队列和暂存方块的预览
 pub fn render_game(
     mut commands: Commands,
     query: Query<Entity, With<Block>>,
+    fixed_time: Res<Time<Fixed>>,
+    state: Res<State<AppState>>,
     game_state: Res<GameState>,
-    board: Res<GameBoard>,
+    playback: Option<Res<ReplayPlayback>>,
+    settings: Res<GameSettings>,
 ) {
+    let game = &game_state.game;
+    let board = &game.board;
+
     // 清除所有上一帧的 Block 实体
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
 
-    // 绘制游戏板（网格和已锁定的方块）
-    for y in 0..GRID_HEIGHT {
+    // 绘制可见区域的背景网格（深灰色）
+    for y in BUFFER_HEIGHT..TOTAL_HEIGHT {
         for x in 0..GRID_WIDTH {
             // 将网格坐标转换为世界坐标
             let (world_x, world_y) = grid_to_world(x as i32, y as i32);
 
-            // 绘制背景网格单元格（深灰色）
             commands.spawn((
                 Sprite {
                     color: Color::srgb(0.1, 0.1, 0.1),
@@ -38,98 +49,364 @@ pub fn render_game(
                 },
                 Transform::from_xyz(world_x, world_y, 0.0), // z=0 在最后面
                 Block,
+                DespawnOnExit(InGame),
             ));
+        }
+    }
 
-            // 如果该格子有已锁定的方块，绘制该方块
-            if let Some(color) = board.cells[y][x] {
-                commands.spawn((
-                    Sprite {
-                        color,
-                        custom_size: Some(Vec2::new(CELL_SIZE - 4.0, CELL_SIZE - 4.0)),
-                        ..default()
-                    },
-                    Transform::from_xyz(world_x, world_y, 1.0), // z=1 在网格上方
-                    Block,
-                ));
+    // 绘制已锁定的方块（包括缓冲区最下方露出的一行）
+    for y in BUFFER_HEIGHT - 1..TOTAL_HEIGHT {
+        for x in 0..GRID_WIDTH {
+            if let Some(cell) = board.cell(x, y) {
+                let color = tetromino_color(cell);
+                spawn_cell(&mut commands, x as i32, y as i32, 0.0, color, 1.0); // z=1 在网格上方
             }
         }
     }
 
+    // 消行等待阶段：待消除的满行以逐渐淡出的白色高亮显示
+    if let GamePhase::LineClear {
+        ref rows,
+        remaining,
+    } = game.phase
+    {
+        let delay = ms_to_frames(game.settings.line_clear_delay_ms);
+        let alpha = if delay > 0 {
+            (remaining as f32 / delay as f32).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        for &y in rows {
+            for x in 0..GRID_WIDTH {
+                spawn_cell(
+                    &mut commands,
+                    x as i32,
+                    y as i32,
+                    0.0,
+                    Color::WHITE.with_alpha(alpha),
+                    1.2,
+                );
+            }
+        }
+    }
+
+    // 绘制幽灵方块（当前方块硬降后的落点，半透明显示）
+    if settings.show_ghost
+        && let Some(ref piece) = game.current_piece
+    {
+        let color = tetromino_color(piece.tetromino_type).with_alpha(0.25);
+        for (x, y) in piece.drop_position(board).blocks() {
+            spawn_cell(&mut commands, x, y, 0.0, color, 1.5); // z=1.5 在已锁定方块上方
+        }
+    }
+
     // 绘制当前下落的活跃方块
-    if let Some(ref piece) = game_state.current_piece {
-        let color = piece.tetromino_type.color();
+    // 模拟以固定帧率运行，按距上一个固定帧经过的时间插值重力下落的位置，使下落更平滑
+    if let Some(ref piece) = game.current_piece {
+        let speed = match (state.get(), playback) {
+            (AppState::Playing, _) => 1.0,
+            (AppState::Replay, Some(playback)) if !playback.paused => playback.speed(),
+            _ => 0.0,
+        };
+        let fraction = game.fall_fraction();
+        let offset = if fraction > 0.0 {
+            let per_frame = gravity(game.level) as f32 / GRAVITY_UNIT as f32;
+            (fraction + fixed_time.overstep_fraction() * per_frame * speed).min(1.0)
+        } else {
+            0.0
+        };
+        let color = tetromino_color(piece.tetromino_type);
         for (x, y) in piece.blocks() {
-            // 只绘制在游戏板可见范围内的方块
-            if y >= 0 && y < GRID_HEIGHT as i32 && x >= 0 && x < GRID_WIDTH as i32 {
-                let (world_x, world_y) = grid_to_world(x, y);
-
-                commands.spawn((
-                    Sprite {
-                        color,
-                        custom_size: Some(Vec2::new(CELL_SIZE - 4.0, CELL_SIZE - 4.0)),
-                        ..default()
-                    },
-                    Transform::from_xyz(world_x, world_y, 2.0), // z=2 在最前面
-                    Block,
-                ));
-            }
+            spawn_cell(&mut commands, x, y, offset, color, 2.0); // z=2 在最前面
         }
     }
 
-    // 绘制下一个方块的预览（游戏运行中显示）
-    if !game_state.game_over {
-        // 计算预览区域的位置（在游戏板右侧）
+    // 绘制后续方块队列和暂存方块的预览（游戏运行中显示）
+    if !game.game_over {
+        // 计算预览区域的位置（后续方块在游戏板右侧，暂存方块在左侧）
         let offset_x = -(GRID_WIDTH as f32) * CELL_SIZE / 2.0;
         let offset_y = GRID_HEIGHT as f32 * CELL_SIZE / 2.0;
         let preview_offset_x = offset_x + GRID_WIDTH as f32 * CELL_SIZE + 60.0;
+        let hold_offset_x = offset_x - 60.0 - PREVIEW_SIZE * 3.0;
         let preview_offset_y = offset_y - 100.0;
 
-        // 获取下一个方块的形状和颜色
-        let next_shape = game_state.next_piece.shape();
-        let next_color = game_state.next_piece.color();
-
-        // 绘制下一个方块的每个格子（缩小显示）
-        for (dx, dy) in next_shape {
-            let world_x = preview_offset_x + dx as f32 * CELL_SIZE * 0.7;
-            let world_y = preview_offset_y - dy as f32 * CELL_SIZE * 0.7;
+        // 后续方块从上到下依次排列，每个方块占 3 个预览格子的高度
+        let count = settings.preview_count.clamp(1, MAX_PREVIEW_COUNT);
+        for (i, &next) in game.next_queue.iter().take(count).enumerate() {
+            let slot_y = preview_offset_y - i as f32 * PREVIEW_SIZE * 3.0;
+            spawn_preview(
+                &mut commands,
+                next,
+                tetromino_color(next),
+                preview_offset_x,
+                slot_y,
+            );
+        }
 
-            commands.spawn((
-                Sprite {
-                    color: next_color,
-                    custom_size: Some(Vec2::new(CELL_SIZE * 0.7 - 4.0, CELL_SIZE * 0.7 - 4.0)),
-                    ..default()
-                },
-                Transform::from_xyz(world_x, world_y, 1.0),
-                Block,
-            ));
+        if let Some(held) = game.hold_piece {
+            // 本方块已使用过暂存时，暂存方块显示为灰色
+            let color = if game.hold_used {
+                Color::srgb(0.4, 0.4, 0.4)
+            } else {
+                tetromino_color(held)
+            };
+            spawn_preview(&mut commands, held, color, hold_offset_x, preview_offset_y);
         }
     }
 }
 
+/// 获取方块类型对应的显示颜色
+fn tetromino_color(tetromino_type: TetrominoType) -> Color {
+    match tetromino_type {
+        TetrominoType::I => Color::srgb(0.0, 0.9, 0.9), // 青色
+        TetrominoType::O => Color::srgb(0.9, 0.9, 0.0), // 黄色
+        TetrominoType::T => Color::srgb(0.7, 0.0, 0.9), // 紫色
+        TetrominoType::S => Color::srgb(0.0, 0.9, 0.0), // 绿色
+        TetrominoType::Z => Color::srgb(0.9, 0.0, 0.0), // 红色
+        TetrominoType::J => Color::srgb(0.0, 0.0, 0.9), // 蓝色
+        TetrominoType::L => Color::srgb(0.9, 0.5, 0.0), // 橙色
+    }
+}
+
+/// 可见区域上方露出的缓冲区行的可见比例
+const PEEK_FRACTION: f32 = 0.3;
+
+/// 在游戏板上绘制一个方块格子
+///
+/// 只绘制可见区域内的格子；缓冲区最下方的一行只露出底部 PEEK_FRACTION 的部分，
+/// 提示玩家可见区域上方的情况，其余缓冲区行不绘制
+///
+/// # 参数
+/// * `offset` - 向下偏移的格数（0 到 1，用于下落插值）
+fn spawn_cell(commands: &mut Commands, x: i32, y: i32, offset: f32, color: Color, z: f32) {
+    let peek_row = BUFFER_HEIGHT as i32 - 1;
+    if x < 0 || x >= GRID_WIDTH as i32 || y < peek_row || y >= TOTAL_HEIGHT as i32 {
+        return;
+    }
+
+    let (world_x, mut world_y) = grid_to_world(x, y);
+    world_y -= offset * CELL_SIZE;
+    let mut height = CELL_SIZE - 4.0;
+    if y == peek_row {
+        // 只保留贴近可见区域顶部的一小条
+        height = CELL_SIZE * PEEK_FRACTION - 2.0;
+        world_y -= (CELL_SIZE - CELL_SIZE * PEEK_FRACTION) / 2.0;
+    }
+
+    commands.spawn((
+        Sprite {
+            color,
+            custom_size: Some(Vec2::new(CELL_SIZE - 4.0, height)),
+            ..default()
+        },
+        Transform::from_xyz(world_x, world_y, z),
+        Block,
+        DespawnOnExit(InGame),
+    ));
+}
+
+/// 预览区中单个格子的边长（游戏板格子的 0.7 倍）
+const PREVIEW_SIZE: f32 = CELL_SIZE * 0.7;
+
+/// 在指定位置缩小绘制一个方块（用于下一个方块和暂存方块的预览）
+fn spawn_preview(
+    commands: &mut Commands,
+    tetromino_type: TetrominoType,
+    color: Color,
+    origin_x: f32,
+    origin_y: f32,
+) {
+    for (dx, dy) in tetromino_type.shape() {
+        let world_x = origin_x + dx as f32 * PREVIEW_SIZE;
+        let world_y = origin_y - dy as f32 * PREVIEW_SIZE;
+
+        commands.spawn((
+            Sprite {
+                color,
+                custom_size: Some(Vec2::new(PREVIEW_SIZE - 4.0, PREVIEW_SIZE - 4.0)),
+                ..default()
+            },
+            Transform::from_xyz(world_x, world_y, 1.0),
+            Block,
+            DespawnOnExit(InGame),
+        ));
+    }
+}
+
 /// UI 文本更新系统
-/// 更新分数显示、游戏结束提示和暂停提示的文本内容
-pub fn update_ui(game_state: Res<GameState>, mut query: Query<(&mut Text, &UiText)>) {
+/// 更新分数显示、用时、游戏结束提示、结算画面、暂停提示和回放状态的文本内容
+pub fn update_ui(
+    state: Res<State<AppState>>,
+    game_state: Res<GameState>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut query: Query<(&mut Text, &UiText)>,
+) {
+    let game = &game_state.game;
     for (mut text, ui_type) in query.iter_mut() {
         // 根据 UI 文本类型更新对应的文本内容
         **text = match ui_type {
             // 分数显示：当前分数
-            UiText::Score => format!("Score: {}", game_state.score),
-            // 游戏结束提示：仅在游戏结束时显示
+            UiText::Score => format!("Score: {}", game.score),
+            // 等级显示：当前等级
+            UiText::Level => format!("Level: {}", game.level),
+            // 消行数显示：累计消除的行数
+            UiText::Lines => match game.settings.mode.line_goal() {
+                Some(goal) => format!("Lines: {}/{}", game.lines, goal),
+                None => format!("Lines: {}", game.lines),
+            },
+            // 模式显示：本局的游戏模式
+            UiText::Mode => format!("Mode: {}", game.settings.mode.label()),
+            // 用时显示：本局经过的模拟时间（限时模式显示剩余时间）
+            UiText::Timer => match game.remaining_frames() {
+                Some(remaining) => format!("Time: {}", format_ms(frames_to_ms(remaining))),
+                None => format!("Time: {}", format_ms(game.elapsed_ms())),
+            },
+            // 种子显示：本局的随机种子（相同种子产生相同的方块序列）
+            UiText::Seed => format!("Seed: {}", game.seed()),
+            // 随机器显示：决定方块出现顺序的随机规则
+            UiText::Randomizer => format!("Randomizer: {}", game.settings.randomizer.name()),
+            // 奖励提示：仅在显示时间内显示
+            UiText::Callout => game
+                .callout
+                .as_ref()
+                .map(|callout| callout.text.clone())
+                .unwrap_or_default(),
+            // 分段用时：冲线模式每消除 10 行时的用时（其他模式不显示）
+            UiText::Splits if !matches!(game.settings.mode, GameMode::Sprint { .. }) => {
+                String::new()
+            }
+            UiText::Splits => game
+                .splits
+                .iter()
+                .enumerate()
+                .map(|(i, &frames)| {
+                    format!(
+                        "{:>3}L {}",
+                        (i as u32 + 1) * SPLIT_LINES,
+                        format_ms(frames_to_ms(frames))
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            // 游戏结束提示：仅在不显示结算画面的溢出结束时显示
             UiText::GameOver => {
-                if game_state.game_over {
-                    "GAME OVER\nPress SPACE".to_string()
+                if game.game_over && !shows_results(game) {
+                    let reason = game.top_out.map(|r| r.label()).unwrap_or_default();
+                    if *state.get() == AppState::GameOver {
+                        format!("GAME OVER\n{reason}\nPress SPACE")
+                    } else {
+                        format!("GAME OVER\n{reason}")
+                    }
+                } else {
+                    String::new()
+                }
+            }
+            // 结算画面：达成模式目标（或马拉松模式结束）时显示本局成绩
+            UiText::Results => {
+                if shows_results(game) {
+                    let mut results = results_text(game);
+                    if *state.get() == AppState::GameOver {
+                        results.push_str("\n\nSPACE: Retry  Q: Menu");
+                    }
+                    results
                 } else {
                     String::new()
                 }
             }
             // 暂停提示：仅在游戏暂停时显示，附带操作说明
             UiText::Pause => {
-                if game_state.paused {
-                    "PAUSED\nESC: Resume\nQ: Quit".to_string()
+                if *state.get() == AppState::Paused {
+                    "PAUSED\nESC: Resume\nQ: Menu".to_string()
                 } else {
                     String::new()
                 }
             }
+            // 回放状态：播放回放时显示进度和操作说明，否则显示回放的保存结果
+            UiText::Replay => match playback {
+                Some(ref playback) => {
+                    let player = &playback.player;
+                    format!(
+                        "REPLAY {} / {}  x{}{}\nSPACE: Pause  \u{2190}/\u{2192}: Seek  \u{2191}/\u{2193}: Speed  Q: Menu",
+                        format_time(player.elapsed()),
+                        format_time(player.replay.duration()),
+                        playback.speed(),
+                        if playback.paused { "  PAUSED" } else { "" },
+                    )
+                }
+                None => game_state.replay_status.clone().unwrap_or_default(),
+            },
         };
     }
 }
+
+/// 是否显示结算画面
+/// 达成模式目标时显示；马拉松模式以分数记录成绩，溢出结束时同样显示
+fn shows_results(game: &Game) -> bool {
+    game.completed || (game.game_over && matches!(game.settings.mode, GameMode::Marathon { .. }))
+}
+
+/// 获取游戏结束后的结算文本
+/// 冲线模式显示用时和操作统计，马拉松和限时模式显示分数及按消行类型拆分的明细
+fn results_text(game: &Game) -> String {
+    let label = game.settings.mode.label();
+    let mut lines = match game.settings.mode {
+        GameMode::Sprint { .. } => {
+            return format!(
+                "{label} CLEAR\nTime {}\nPieces {}\nPPS {:.2}\nFinesse faults {}",
+                format_ms(game.elapsed_ms()),
+                game.pieces,
+                game.pieces_per_second(),
+                game.finesse_faults,
+            );
+        }
+        GameMode::Ultra { .. } => vec![format!("{label} TIME UP")],
+        GameMode::Marathon { .. } if game.completed => vec![format!("{label} CLEAR")],
+        GameMode::Marathon { .. } => {
+            let reason = game.top_out.map(|r| r.label()).unwrap_or_default();
+            vec![format!("{label}  GAME OVER"), reason.to_string()]
+        }
+    };
+
+    // 分数明细：各消行类型的次数和得分，以及奖励分和下落分
+    let breakdown = &game.score_breakdown;
+    lines.push(format!("Score {}", game.score));
+    lines.extend(
+        breakdown
+            .clears
+            .iter()
+            .map(|clear| format!("{} x{}  {}", clear.name, clear.count, clear.points)),
+    );
+    for (name, points) in [
+        ("B2B BONUS", breakdown.back_to_back),
+        ("COMBO BONUS", breakdown.combo),
+        ("PERFECT CLEAR BONUS", breakdown.perfect_clear),
+        ("DROP", breakdown.drop),
+    ] {
+        if points > 0 {
+            lines.push(format!("{name}  {points}"));
+        }
+    }
+    lines.push(format!(
+        "Level {}  Lines {}  Time {}",
+        game.level,
+        game.lines,
+        format_ms(game.elapsed_ms())
+    ));
+    lines.push(format!(
+        "Pieces {}  PPS {:.2}",
+        game.pieces,
+        game.pieces_per_second()
+    ));
+    lines.join("\n")
+}
+
+/// 将毫秒数格式化为 分:秒.毫秒（如 1:05.250）
+pub(crate) fn format_ms(ms: u64) -> String {
+    format!("{}:{:02}.{:03}", ms / 60_000, ms / 1000 % 60, ms % 1000)
+}
+
+/// 将秒数格式化为 分:秒.十分之一秒（如 1:05.3）
+fn format_time(seconds: f32) -> String {
+    let tenths = (seconds * 10.0) as u32;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
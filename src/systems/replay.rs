@@ -0,0 +1,148 @@
+// 回放系统
+// 保存和加载回放文件，按播放速度把录制的输入交给游戏模拟
+
+use crate::resources::{GameState, ReplayPlayback};
+use crate::states::AppState;
+use bevy::prelude::*;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tetris_core::Replay;
+use tetris_core::constants::FRAME_RATE;
+
+/// 回放文件的保存目录
+const REPLAY_DIR: &str = "replays";
+
+/// 每次按左右键跳转的时间（秒）
+const SEEK_SECONDS: usize = 5;
+
+/// 将回放保存到回放目录
+///
+/// 文件名由保存时间和种子组成，如 `replays/1700000000-12345.replay`；
+/// 同名文件已存在时（如固定种子的两局在同一秒结束）加上序号，如 `1700000000-12345-1.replay`，
+/// 已有的回放不会被覆盖
+///
+/// # 返回
+/// 保存成功时返回文件路径
+pub fn save_replay(replay: &Replay) -> io::Result<String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    fs::create_dir_all(REPLAY_DIR)?;
+    let text = replay.to_text();
+    let mut path = format!("{REPLAY_DIR}/{timestamp}-{}.replay", replay.seed);
+    let mut suffix = 0;
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                suffix += 1;
+                path = format!("{REPLAY_DIR}/{timestamp}-{}-{suffix}.replay", replay.seed);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// 列出回放目录中的所有回放文件
+///
+/// # 返回
+/// 回放文件路径，按保存时间从新到旧排列（目录不存在时返回空列表）
+pub fn list_replays() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(REPLAY_DIR) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "replay")
+        })
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    // 文件名以保存时间开头，按名称倒序即为从新到旧
+    paths.sort_unstable_by(|a, b| b.cmp(a));
+    paths
+}
+
+/// 从文件加载回放
+///
+/// # 返回
+/// 加载失败时返回可直接显示的错误信息
+pub fn load_replay(path: &str) -> Result<Replay, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("无法读取 {path}：{error}"))?;
+    Replay::parse(&text).map_err(|error| error.to_string())
+}
+
+/// 回放操作处理系统
+/// 处理回放播放时的键盘输入：
+/// - 暂停/继续（空格）
+/// - 后退/快进 SEEK_SECONDS 秒（左右方向键）
+/// - 减速/加速（下/上方向键）
+/// - 返回主菜单（Q）
+pub fn handle_replay_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut game_state: ResMut<GameState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyQ) {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::Space) {
+        playback.paused = !playback.paused;
+    }
+
+    // 调整播放速度
+    if keyboard.just_pressed(KeyCode::ArrowUp) {
+        playback.speed_index = (playback.speed_index + 1).min(ReplayPlayback::SPEEDS.len() - 1);
+    }
+    if keyboard.just_pressed(KeyCode::ArrowDown) {
+        playback.speed_index = playback.speed_index.saturating_sub(1);
+    }
+
+    // 跳转：向前跳转会从头重新模拟
+    let step = SEEK_SECONDS * FRAME_RATE as usize;
+    let position = playback.player.position();
+    let target = if keyboard.just_pressed(KeyCode::ArrowLeft) {
+        Some(position.saturating_sub(step))
+    } else if keyboard.just_pressed(KeyCode::ArrowRight) {
+        Some(position + step)
+    } else {
+        None
+    };
+    if let Some(target) = target {
+        playback.player.seek(&mut game_state.game, target);
+        playback.pending = 0.0;
+    }
+}
+
+/// 回放播放系统
+/// 每个固定帧按播放速度累计要播放的帧数（如 0.5 倍速每两个固定帧播放一帧），
+/// 依次播放已累计满的录制输入
+pub fn update_replay(mut playback: ResMut<ReplayPlayback>, mut game_state: ResMut<GameState>) {
+    if playback.paused || playback.player.is_finished() {
+        return;
+    }
+
+    playback.pending += playback.speed();
+    while playback.pending >= 1.0 {
+        playback.pending -= 1.0;
+        if !playback.player.step(&mut game_state.game) {
+            break;
+        }
+    }
+}
+
+/// 结束回放播放
+/// 离开回放状态时移除回放播放资源
+pub fn end_replay(mut commands: Commands) {
+    commands.remove_resource::<ReplayPlayback>();
+}